@@ -0,0 +1,53 @@
+use bencher::Bencher;
+use resvg::usvg;
+
+// A dense scatter plot: 40000 sub-pixel circles.
+fn scatter_svg() -> String {
+    let mut svg = String::from("<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>");
+    for y in 0..200 {
+        for x in 0..200 {
+            svg.push_str(&format!("<circle cx='{}.5' cy='{}.5' r='0.2'/>", x, y));
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+macro_rules! bench_backend {
+    ($name:ident, $backend:ident, $cull_threshold:expr) => {
+        fn $name(bencher: &mut Bencher) {
+            let tree = usvg::Tree::from_str(&scatter_svg(), &usvg::Options::default()).unwrap();
+            let opt = resvg::Options {
+                cull_threshold: $cull_threshold,
+                ..resvg::Options::default()
+            };
+            bencher.iter(|| {
+                let _ = bencher::black_box(resvg::$backend::render_to_image(&tree, &opt));
+            })
+        }
+    };
+}
+
+bench_backend!(scatter_cairo, backend_cairo, None);
+bench_backend!(scatter_qt, backend_qt, None);
+bench_backend!(scatter_raqote, backend_raqote, None);
+bench_backend!(scatter_skia, backend_skia, None);
+
+bench_backend!(scatter_culled_cairo, backend_cairo, Some(1.0));
+bench_backend!(scatter_culled_qt, backend_qt, Some(1.0));
+bench_backend!(scatter_culled_raqote, backend_raqote, Some(1.0));
+bench_backend!(scatter_culled_skia, backend_skia, Some(1.0));
+
+bencher::benchmark_group!(
+    benches,
+    scatter_cairo,
+    scatter_qt,
+    scatter_raqote,
+    scatter_skia,
+    scatter_culled_cairo,
+    scatter_culled_qt,
+    scatter_culled_raqote,
+    scatter_culled_skia
+);
+
+bencher::benchmark_main!(benches);
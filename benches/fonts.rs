@@ -0,0 +1,32 @@
+use bencher::Bencher;
+use resvg::usvg;
+
+const TEXT_SVG: &str = "
+<svg viewBox='0 0 200 100' xmlns='http://www.w3.org/2000/svg'>
+    <text x='10' y='50' font-family='serif' font-size='32'>Text</text>
+</svg>
+";
+
+// A new `Options` for each conversion, so system fonts are scanned each time.
+fn text_new_font_db(bencher: &mut Bencher) {
+    bencher.iter(|| {
+        let opt = usvg::Options::default();
+        let _ = bencher::black_box(usvg::Tree::from_str(TEXT_SVG, &opt));
+    })
+}
+
+// The same `Options` for all conversions, so system fonts are scanned only once.
+fn text_shared_font_db(bencher: &mut Bencher) {
+    let opt = usvg::Options::default();
+    bencher.iter(|| {
+        let _ = bencher::black_box(usvg::Tree::from_str(TEXT_SVG, &opt));
+    })
+}
+
+bencher::benchmark_group!(
+    benches,
+    text_new_font_db,
+    text_shared_font_db
+);
+
+bencher::benchmark_main!(benches);
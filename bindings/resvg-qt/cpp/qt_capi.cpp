@@ -210,6 +210,11 @@ void qtc_qpainter_set_clip_rect(qtc_qpainter *c_p, double x, double y, double w,
     PAINTER_CAST->setClipRect(QRectF(x, y, w, h));
 }
 
+void qtc_qpainter_intersect_clip_rect(qtc_qpainter *c_p, double x, double y, double w, double h)
+{
+    PAINTER_CAST->setClipRect(QRectF(x, y, w, h), Qt::IntersectClip);
+}
+
 void qtc_qpainter_set_clip_path(qtc_qpainter *c_p, qtc_qpainterpath *c_pp)
 {
     PAINTER_CAST->setClipPath(*PATH_CAST);
@@ -225,6 +230,16 @@ void qtc_qpainter_set_composition_mode(qtc_qpainter *c_p, CompositionMode mode)
     PAINTER_CAST->setCompositionMode(QPainter::CompositionMode(mode));
 }
 
+void qtc_qpainter_save(qtc_qpainter *c_p)
+{
+    PAINTER_CAST->save();
+}
+
+void qtc_qpainter_restore(qtc_qpainter *c_p)
+{
+    PAINTER_CAST->restore();
+}
+
 void qtc_qpainter_end(qtc_qpainter *c_p)
 {
     PAINTER_CAST->end();
@@ -128,9 +128,12 @@ void qtc_qpainter_scale(qtc_qpainter *c_p, double sx, double sy);
 qtc_qtransform* qtc_qpainter_get_transform(qtc_qpainter *c_p);
 void qtc_qpainter_set_transform(qtc_qpainter *c_p, qtc_qtransform *q_ts, bool combine);
 void qtc_qpainter_set_clip_rect(qtc_qpainter *c_p, double x, double y, double w, double h);
+void qtc_qpainter_intersect_clip_rect(qtc_qpainter *c_p, double x, double y, double w, double h);
 void qtc_qpainter_set_clip_path(qtc_qpainter *c_p, qtc_qpainterpath *c_pp);
 void qtc_qpainter_reset_clip_path(qtc_qpainter *c_p);
 void qtc_qpainter_set_composition_mode(qtc_qpainter *c_p, CompositionMode mode);
+void qtc_qpainter_save(qtc_qpainter *c_p);
+void qtc_qpainter_restore(qtc_qpainter *c_p);
 void qtc_qpainter_end(qtc_qpainter *c_p);
 void qtc_qpainter_destroy(qtc_qpainter *c_p);
 
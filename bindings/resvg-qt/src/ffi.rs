@@ -526,6 +526,9 @@ extern "C" {
 extern "C" {
     pub fn qtc_qpainter_set_clip_rect(c_p: *mut qtc_qpainter, x: f64, y: f64, w: f64, h: f64);
 }
+extern "C" {
+    pub fn qtc_qpainter_intersect_clip_rect(c_p: *mut qtc_qpainter, x: f64, y: f64, w: f64, h: f64);
+}
 extern "C" {
     pub fn qtc_qpainter_set_clip_path(c_p: *mut qtc_qpainter, c_pp: *mut qtc_qpainterpath);
 }
@@ -535,6 +538,12 @@ extern "C" {
 extern "C" {
     pub fn qtc_qpainter_set_composition_mode(c_p: *mut qtc_qpainter, mode: CompositionMode);
 }
+extern "C" {
+    pub fn qtc_qpainter_save(c_p: *mut qtc_qpainter);
+}
+extern "C" {
+    pub fn qtc_qpainter_restore(c_p: *mut qtc_qpainter);
+}
 extern "C" {
     pub fn qtc_qpainter_end(c_p: *mut qtc_qpainter);
 }
@@ -264,6 +264,10 @@ impl Painter {
         unsafe { ffi::qtc_qpainter_set_clip_rect(self.0, x, y, w, h) }
     }
 
+    pub fn intersect_clip_rect(&mut self, x: f64, y: f64, w: f64, h: f64) {
+        unsafe { ffi::qtc_qpainter_intersect_clip_rect(self.0, x, y, w, h) }
+    }
+
     pub fn set_clip_path(&mut self, path: &PainterPath) {
         unsafe { ffi::qtc_qpainter_set_clip_path(self.0, path.0) }
     }
@@ -276,6 +280,14 @@ impl Painter {
         unsafe { ffi::qtc_qpainter_set_composition_mode(self.0, mode as ffi::CompositionMode) }
     }
 
+    pub fn save(&mut self) {
+        unsafe { ffi::qtc_qpainter_save(self.0) }
+    }
+
+    pub fn restore(&mut self) {
+        unsafe { ffi::qtc_qpainter_restore(self.0) }
+    }
+
     pub fn end(&mut self) {
         unsafe { ffi::qtc_qpainter_end(self.0) }
     }
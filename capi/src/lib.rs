@@ -673,7 +673,7 @@ pub extern "C" fn resvg_node_exists(
         &*tree
     };
 
-    tree.0.node_by_id(id).is_some()
+    tree.0.node_by_id(id).map_or(false, |node| !tree.0.is_in_defs(&node))
 }
 
 #[no_mangle]
@@ -757,10 +757,11 @@ fn to_native_opt(
     };
 
     let background = if opt.draw_background {
-        Some(usvg::Color::new(
+        Some(usvg::ColorA::new(
             opt.background.r,
             opt.background.g,
             opt.background.b,
+            255,
         ))
     } else {
         None
@@ -839,16 +840,31 @@ fn to_native_opt(
         usvg: usvg::Options {
             path,
             dpi: opt.dpi,
+            dpi_x: None,
+            dpi_y: None,
             font_family: font_family.to_string(),
             font_size: opt.font_size,
+            default_stroke_width: 1.0,
+            container_size: None,
             languages,
             shape_rendering,
             text_rendering,
             image_rendering,
             keep_named_groups: opt.keep_named_groups,
+            keep_image_data: false,
+            foreign_object_placeholder: false,
+            animation_time: None,
+            warning_handler: None,
+            font_db: usvg::FontDatabase::new(),
         },
         fit_to,
         background,
+        default_background: None,
+        offset: (0, 0),
+        outline_mode: None,
+        pixel_snap: false,
+        cull_threshold: None,
+        clip_to_viewbox: true,
     }
 }
 
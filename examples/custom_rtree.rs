@@ -14,6 +14,7 @@ fn main() {
             rect: size.to_rect(0.0, 0.0),
             aspect: usvg::AspectRatio::default(),
         },
+        metadata: None,
     });
 
     rtree.append_to_defs(usvg::NodeKind::LinearGradient(usvg::LinearGradient {
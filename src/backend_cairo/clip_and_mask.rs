@@ -151,7 +151,8 @@ pub fn mask(
         super::render_group(node, opt, &mut RenderState::Ok, layers, &mask_cr);
     }
 
-    {
+    // An alpha mask uses the alpha channel as is.
+    if mask.kind == usvg::MaskType::Luminance {
         let mut data = try_opt_warn!(
             mask_surface.get_data().ok(),
             "Failed to borrow a surface for mask '{}'.", mask.id
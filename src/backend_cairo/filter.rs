@@ -6,7 +6,6 @@ use std::cmp;
 use std::rc::Rc;
 
 use rgb::FromSlice;
-use log::warn;
 use usvg::ColorInterpolation as ColorSpace;
 
 use crate::prelude::*;
@@ -410,7 +409,7 @@ impl Filter<cairo::ImageSurface> for CairoFilter {
                 if format == usvg::ImageFormat::SVG {
                     super::image::draw_svg(data, view_box, opt, &cr);
                 } else {
-                    super::image::draw_raster(format, data, view_box, fe.rendering_mode, opt, &cr);
+                    super::image::draw_raster(format, data, view_box, fe.rendering_mode, &cr);
                 }
             }
             usvg::FeImageKind::Use(ref id) => {
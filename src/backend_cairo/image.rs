@@ -19,7 +19,7 @@ pub fn draw(
     if image.format == usvg::ImageFormat::SVG {
         draw_svg(&image.data, image.view_box, opt, cr);
     } else {
-        draw_raster(image.format, &image.data, image.view_box, image.rendering_mode, opt, cr);
+        draw_raster(image.format, &image.data, image.view_box, image.rendering_mode, cr);
     }
 
     image.view_box.rect
@@ -30,10 +30,9 @@ pub fn draw_raster(
     data: &usvg::ImageData,
     view_box: usvg::ViewBox,
     rendering_mode: usvg::ImageRendering,
-    opt: &Options,
     cr: &cairo::Context,
 ) {
-    let img = try_opt!(image::load_raster(format, data, opt));
+    let img = try_opt!(image::load_raster(format, data));
 
     let surface = {
         let mut surface = try_create_surface!(img.size, ());
@@ -49,6 +48,8 @@ pub fn draw_raster(
 
     let (ts, clip) = image::prepare_sub_svg_geom(view_box, img.size);
 
+    cr.save();
+
     if let Some(clip) = clip {
         cr.rectangle(clip.x(), clip.y(), clip.width(), clip.height());
         cr.clip();
@@ -72,7 +73,8 @@ pub fn draw_raster(
     patt.set_filter(filter_mode);
     cr.set_source(&patt);
     cr.paint();
-    cr.reset_clip();
+
+    cr.restore();
 }
 
 fn image_to_surface(image: &image::Image, surface: &mut [u8]) {
@@ -120,6 +122,8 @@ pub fn draw_svg(
     let img_size = tree.svg_node().size.to_screen_size();
     let (ts, clip) = image::prepare_sub_svg_geom(view_box, img_size);
 
+    cr.save();
+
     if let Some(clip) = clip {
         cr.rectangle(clip.x(), clip.y(), clip.width(), clip.height());
         cr.clip();
@@ -127,5 +131,6 @@ pub fn draw_svg(
 
     cr.transform(ts.to_native());
     super::render_to_canvas(&tree, &sub_opt, img_size, cr);
-    cr.reset_clip();
+
+    cr.restore();
 }
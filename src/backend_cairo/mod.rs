@@ -4,8 +4,6 @@
 
 //! Cairo backend implementation.
 
-use log::warn;
-
 use crate::{prelude::*, layers, ConvTransform, RenderState};
 
 
@@ -136,22 +134,22 @@ pub fn render_to_image(
     tree: &usvg::Tree,
     opt: &Options,
 ) -> Option<cairo::ImageSurface> {
-    let (surface, img_view) = create_surface(
-        tree.svg_node().size.to_screen_size(),
-        opt,
-    )?;
+    let _guard = usvg::set_warning_handler(opt.usvg.warning_handler.as_ref());
+
+    let (view_box, size) = utils::image_view_box(tree, opt);
+    let (surface, img_view) = create_surface(size, opt)?;
 
     let cr = cairo::Context::new(&surface);
 
     // Fill background.
-    if let Some(color) = opt.background {
-        cr.set_source_color(color, 1.0.into());
+    if let Some(color) = opt.resolved_background() {
+        cr.set_source_color(color.to_color(), (color.alpha as f64 / 255.0).into());
         cr.paint();
     }
 
-    render_to_canvas(tree, opt, img_view, &cr);
+    render_node_to_canvas(&tree.root(), opt, view_box, img_view, &cr);
 
-    Some(surface)
+    apply_offset(surface, opt)
 }
 
 /// Renders SVG to image.
@@ -159,6 +157,8 @@ pub fn render_node_to_image(
     node: &usvg::Node,
     opt: &Options,
 ) -> Option<cairo::ImageSurface> {
+    let _guard = usvg::set_warning_handler(opt.usvg.warning_handler.as_ref());
+
     let node_bbox = if let Some(bbox) = node.calculate_bbox() {
         bbox
     } else {
@@ -176,14 +176,32 @@ pub fn render_node_to_image(
     let cr = cairo::Context::new(&surface);
 
     // Fill background.
-    if let Some(color) = opt.background {
-        cr.set_source_color(color, 1.0.into());
+    if let Some(color) = opt.resolved_background() {
+        cr.set_source_color(color.to_color(), (color.alpha as f64 / 255.0).into());
         cr.paint();
     }
 
     render_node_to_canvas(node, opt, vbox, img_size, &cr);
 
-    Some(surface)
+    apply_offset(surface, opt)
+}
+
+/// Renders a node with the specified ID to an image of the node's bounding box size.
+///
+/// Returns the image and the bounding box in the root element coordinates,
+/// which can be used to position the image. Useful to export icons from a sprite sheet.
+///
+/// Returns `None` when a renderable node with this ID doesn't exist
+/// or has a zero size, or when an image allocation failed.
+pub fn render_node_by_id_to_image(
+    tree: &usvg::Tree,
+    opt: &Options,
+    id: &str,
+) -> Option<(cairo::ImageSurface, Rect)> {
+    let node = tree.node_by_id(id)?;
+    let bbox = node.calculate_bbox()?;
+    let img = render_node_to_image(&node, opt)?;
+    Some((img, bbox))
 }
 
 /// Renders SVG to canvas.
@@ -196,6 +214,30 @@ pub fn render_to_canvas(
     render_node_to_canvas(&tree.root(), opt, tree.svg_node().view_box, img_size, cr);
 }
 
+/// Renders SVG to canvas, limited to the specified rect.
+///
+/// `rect` is in canvas pixels and is not affected by the current canvas transform.
+/// Pixels outside of it are left untouched, which is useful for partial repaints.
+pub fn render_rect_to_canvas(
+    tree: &usvg::Tree,
+    opt: &Options,
+    img_size: ScreenSize,
+    rect: ScreenRect,
+    cr: &cairo::Context,
+) {
+    cr.save();
+
+    let ts = cr.get_matrix();
+    cr.identity_matrix();
+    cr.rectangle(rect.x() as f64, rect.y() as f64, rect.width() as f64, rect.height() as f64);
+    cr.clip();
+    cr.set_matrix(ts);
+
+    render_to_canvas(tree, opt, img_size, cr);
+
+    cr.restore();
+}
+
 /// Renders SVG node to canvas.
 pub fn render_node_to_canvas(
     node: &usvg::Node,
@@ -204,6 +246,8 @@ pub fn render_node_to_canvas(
     img_size: ScreenSize,
     cr: &cairo::Context,
 ) {
+    let _guard = usvg::set_warning_handler(opt.usvg.warning_handler.as_ref());
+
     render_node_to_canvas_impl(node, opt, view_box, img_size, &mut RenderState::Ok, cr)
 }
 
@@ -239,6 +283,35 @@ fn create_surface(
     Some((surface, img_size))
 }
 
+/// Moves the image by `Options::offset`.
+fn apply_offset(
+    surface: cairo::ImageSurface,
+    opt: &Options,
+) -> Option<cairo::ImageSurface> {
+    let (dx, dy) = opt.offset;
+    if dx == 0 && dy == 0 {
+        return Some(surface);
+    }
+
+    let size = ScreenSize::new(surface.get_width() as u32, surface.get_height() as u32)?;
+    let size = utils::apply_offset(size, opt.offset)?;
+
+    let new_surface = try_create_surface!(size, None);
+
+    let cr = cairo::Context::new(&new_surface);
+
+    // Fill background.
+    if let Some(color) = opt.resolved_background() {
+        cr.set_source_color(color.to_color(), (color.alpha as f64 / 255.0).into());
+        cr.paint();
+    }
+
+    cr.set_source_surface(&surface, dx as f64, dy as f64);
+    cr.paint();
+
+    Some(new_surface)
+}
+
 /// Applies viewbox transformation to the painter.
 fn apply_viewbox_transform(
     view_box: usvg::ViewBox,
@@ -261,6 +334,15 @@ fn render_node(
             render_group(node, opt, state, layers, cr)
         }
         usvg::NodeKind::Path(ref path) => {
+            let ts = usvg::Transform::from_native(&cr.get_matrix());
+            if crate::is_culled(path, ts, opt) {
+                return None;
+            }
+
+            let outline = crate::outline_path(path, opt);
+            let path = outline.as_ref().unwrap_or(path);
+            let snapped = crate::snap_path(path, ts, opt);
+            let path = snapped.as_ref().unwrap_or(path);
             path::draw(&node.tree(), path, opt, cr)
         }
         usvg::NodeKind::Image(ref img) => {
@@ -509,3 +591,51 @@ fn clear_subsurface(
     cr.set_source_rgba(0.0, 0.0, 0.0, 0.0);
     cr.paint();
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_rect_to_canvas_keeps_outside() {
+        let svg = "
+        <svg viewBox='0 0 50 50' xmlns='http://www.w3.org/2000/svg'>
+            <rect width='50' height='50' fill='#00ff00'/>
+        </svg>
+        ";
+
+        let opt = Options::default();
+        let tree = usvg::Tree::from_str(svg, &opt.usvg).unwrap();
+
+        // Render at a device pixel ratio of 2 into an existing image.
+        let mut surface = cairo::ImageSurface::create(cairo::Format::ARgb32, 100, 100).unwrap();
+        {
+            let cr = cairo::Context::new(&surface);
+            cr.set_source_rgb(1.0, 0.0, 0.0);
+            cr.paint();
+            cr.scale(2.0, 2.0);
+
+            let img_size = ScreenSize::new(50, 50).unwrap();
+            let rect = ScreenRect::new(20, 30, 40, 10).unwrap();
+            render_rect_to_canvas(&tree, &opt, img_size, rect, &cr);
+
+            // The canvas state is restored.
+            assert_eq!(cr.get_matrix(), cairo::Matrix::new(2.0, 0.0, 0.0, 2.0, 0.0, 0.0));
+        }
+
+        // The rect is in device pixels.
+        let stride = surface.get_stride() as usize;
+        let data = surface.get_data().unwrap();
+        let pixel = |x: usize, y: usize| {
+            let i = y * stride + x * 4;
+            u32::from_ne_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]])
+        };
+        for &(x, y) in &[(20, 30), (59, 30), (40, 35), (59, 39)] {
+            assert_eq!(pixel(x, y), 0xff00ff00, "{} {}", x, y);
+        }
+        for &(x, y) in &[(19, 35), (60, 35), (40, 29), (40, 40), (90, 90)] {
+            assert_eq!(pixel(x, y), 0xffff0000, "{} {}", x, y);
+        }
+    }
+}
@@ -33,14 +33,27 @@ pub fn draw(
         cr.set_antialias(cairo::Antialias::None);
     }
 
-    style::fill(tree, &path.fill, opt, style_bbox, cr);
-    if path.stroke.is_some() {
-        cr.fill_preserve();
+    match path.paint_order {
+        usvg::PaintOrder::FillAndStroke => {
+            style::fill(tree, &path.fill, opt, style_bbox, cr);
+            if path.stroke.is_some() {
+                cr.fill_preserve();
+
+                style::stroke(tree, &path.stroke, opt, style_bbox, cr);
+                cr.stroke();
+            } else {
+                cr.fill();
+            }
+        }
+        usvg::PaintOrder::StrokeAndFill => {
+            if path.stroke.is_some() {
+                style::stroke(tree, &path.stroke, opt, style_bbox, cr);
+                cr.stroke_preserve();
+            }
 
-        style::stroke(tree, &path.stroke, opt, style_bbox, cr);
-        cr.stroke();
-    } else {
-        cr.fill();
+            style::fill(tree, &path.fill, opt, style_bbox, cr);
+            cr.fill();
+        }
     }
 
     // Revert anti-aliasing.
@@ -58,6 +58,9 @@ pub fn stroke(
 ) {
     match *stroke {
         Some(ref stroke) => {
+            let ts = usvg::Transform::from_native(&cr.get_matrix());
+            let stroke = &*stroke.resolve_non_scaling(ts);
+
             match stroke.paint {
                 usvg::Paint::Color(c) => {
                     cr.set_source_color(c, stroke.opacity);
@@ -149,16 +152,18 @@ fn prepare_base_gradient(
     };
     grad.set_extend(spread_method);
 
-    let mut matrix = g.transform.to_native();
+    let ts = if g.units == usvg::Units::ObjectBoundingBox {
+        let mut ts = usvg::Transform::from_bbox(bbox);
+        ts.append(&g.transform);
+        ts
+    } else {
+        g.transform
+    };
 
-    if g.units == usvg::Units::ObjectBoundingBox {
-        let m = usvg::Transform::from_bbox(bbox).to_native();
-        matrix = cairo::Matrix::multiply(&matrix, &m);
+    if let Some(ts) = ts.invert() {
+        grad.set_matrix(ts.to_native());
     }
 
-    matrix.invert();
-    grad.set_matrix(matrix);
-
     for stop in &g.stops {
         grad.add_color_stop_rgba(
             stop.offset.value(),
@@ -143,8 +143,11 @@ pub fn mask(
         super::render_group(node, opt, &mut RenderState::Ok, layers, &mut mask_p);
     }
 
-    use rgb::FromSlice;
-    crate::image_to_mask(mask_img.data_mut().as_bgra_mut(), layers.image_size());
+    // An alpha mask uses the alpha channel as is.
+    if mask.kind == usvg::MaskType::Luminance {
+        use rgb::FromSlice;
+        crate::image_to_mask(mask_img.data_mut().as_bgra_mut(), layers.image_size());
+    }
 
     if let Some(ref id) = mask.mask {
         if let Some(ref mask_node) = node.tree().defs_by_id(id) {
@@ -7,7 +7,6 @@ use std::rc::Rc;
 
 use crate::qt;
 use rgb::FromSlice;
-use log::warn;
 use usvg::ColorInterpolation as ColorSpace;
 
 use crate::prelude::*;
@@ -389,7 +388,7 @@ impl Filter<qt::Image> for QtFilter {
                     super::image::draw_svg(data, view_box, opt, &mut p);
                 } else {
                     super::image::draw_raster(
-                        format, data, view_box, fe.rendering_mode, opt, &mut p
+                        format, data, view_box, fe.rendering_mode, &mut p
                     );
                 }
             }
@@ -21,7 +21,7 @@ pub fn draw(
     if image.format == usvg::ImageFormat::SVG {
         draw_svg(&image.data, image.view_box, opt, p);
     } else {
-        draw_raster(image.format, &image.data, image.view_box, image.rendering_mode, opt, p);
+        draw_raster(image.format, &image.data, image.view_box, image.rendering_mode, p);
     }
 
     image.view_box.rect
@@ -32,10 +32,9 @@ pub fn draw_raster(
     data: &usvg::ImageData,
     view_box: usvg::ViewBox,
     rendering_mode: usvg::ImageRendering,
-    opt: &Options,
     p: &mut qt::Painter,
 ) {
-    let img = try_opt!(image::load_raster(format, data, opt));
+    let img = try_opt!(image::load_raster(format, data));
 
     let image = {
         let (w, h) = img.size.dimensions();
@@ -47,21 +46,22 @@ pub fn draw_raster(
         image
     };
 
+    p.save();
+
     if rendering_mode == usvg::ImageRendering::OptimizeSpeed {
         p.set_smooth_pixmap_transform(false);
     }
 
     if view_box.aspect.slice {
         let r = view_box.rect;
-        p.set_clip_rect(r.x(), r.y(), r.width(), r.height());
+        p.intersect_clip_rect(r.x(), r.y(), r.width(), r.height());
     }
 
     let r = image::image_rect(&view_box, img.size);
     p.draw_image_rect(r.x(), r.y(), r.width(), r.height(), &image);
 
     // Revert.
-    p.set_smooth_pixmap_transform(true);
-    p.reset_clip_path();
+    p.restore();
 }
 
 fn image_to_surface(image: &image::Image, surface: &mut [u8]) {
@@ -107,11 +107,14 @@ pub fn draw_svg(
     let img_size = tree.svg_node().size.to_screen_size();
     let (ts, clip) = image::prepare_sub_svg_geom(view_box, img_size);
 
+    p.save();
+
     if let Some(clip) = clip {
-        p.set_clip_rect(clip.x(), clip.y(), clip.width(), clip.height());
+        p.intersect_clip_rect(clip.x(), clip.y(), clip.width(), clip.height());
     }
 
     p.apply_transform(&ts.to_native());
     super::render_to_canvas(&tree, &sub_opt, img_size, p);
-    p.reset_clip_path();
+
+    p.restore();
 }
@@ -32,11 +32,25 @@ pub fn draw(
     // so we can pass whatever rect we want, because it will not be used anyway.
     let style_bbox = bbox.unwrap_or_else(|| Rect::new(0.0, 0.0, 1.0, 1.0).unwrap());
 
-    style::fill(tree, &path.fill, opt, style_bbox, p);
-    style::stroke(tree, &path.stroke, opt, style_bbox, p);
     p.set_antialiasing(crate::use_shape_antialiasing(path.rendering_mode));
 
-    p.draw_path(&new_path);
+    match path.paint_order {
+        usvg::PaintOrder::FillAndStroke => {
+            style::fill(tree, &path.fill, opt, style_bbox, p);
+            style::stroke(tree, &path.stroke, opt, style_bbox, p);
+            p.draw_path(&new_path);
+        }
+        usvg::PaintOrder::StrokeAndFill => {
+            // Qt always draws a fill first, so we have to draw the path twice.
+            style::fill(tree, &None, opt, style_bbox, p);
+            style::stroke(tree, &path.stroke, opt, style_bbox, p);
+            p.draw_path(&new_path);
+
+            style::fill(tree, &path.fill, opt, style_bbox, p);
+            style::stroke(tree, &None, opt, style_bbox, p);
+            p.draw_path(&new_path);
+        }
+    }
 
     // Revert anti-aliasing.
     p.set_antialiasing(true);
@@ -60,6 +60,9 @@ pub fn stroke(
 ) {
     match *stroke {
         Some(ref stroke) => {
+            let ts = usvg::Transform::from_native(&p.get_transform());
+            let stroke = &*stroke.resolve_non_scaling(ts);
+
             let mut pen = qt::Pen::new();
             let opacity = stroke.opacity;
 
@@ -6,7 +6,6 @@ use std::cmp;
 use std::rc::Rc;
 
 use rgb::FromSlice;
-use log::warn;
 
 use usvg::ColorInterpolation as ColorSpace;
 
@@ -411,7 +410,7 @@ impl Filter<raqote::DrawTarget> for RaqoteFilter {
                     super::image::draw_svg(data, view_box, opt, &mut dt);
                 } else {
                     super::image::draw_raster(
-                        format, data, view_box, fe.rendering_mode, opt, &mut dt
+                        format, data, view_box, fe.rendering_mode, &mut dt
                     );
                 }
             }
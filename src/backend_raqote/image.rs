@@ -20,7 +20,7 @@ pub fn draw(
     if image.format == usvg::ImageFormat::SVG {
         draw_svg(&image.data, image.view_box, opt, dt);
     } else {
-        draw_raster(image.format, &image.data, image.view_box, image.rendering_mode, opt, dt);
+        draw_raster(image.format, &image.data, image.view_box, image.rendering_mode, dt);
     }
 
     image.view_box.rect
@@ -31,10 +31,9 @@ pub fn draw_raster(
     data: &usvg::ImageData,
     view_box: usvg::ViewBox,
     rendering_mode: usvg::ImageRendering,
-    opt: &Options,
     dt: &mut raqote::DrawTarget,
 ) {
-    let img = try_opt!(image::load_raster(format, data, opt));
+    let img = try_opt!(image::load_raster(format, data));
 
     let sub_dt = {
         let mut sub_dt = raqote::DrawTarget::new(img.size.width() as i32, img.size.height() as i32);
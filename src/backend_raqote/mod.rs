@@ -4,8 +4,6 @@
 
 //! Raqote backend implementation.
 
-use log::warn;
-
 use crate::{prelude::*, layers, ConvTransform, RenderState};
 
 mod clip_and_mask;
@@ -138,19 +136,19 @@ pub fn render_to_image(
     tree: &usvg::Tree,
     opt: &Options,
 ) -> Option<raqote::DrawTarget> {
-    let (mut dt, img_view) = create_target(
-        tree.svg_node().size.to_screen_size(),
-        opt,
-    )?;
+    let _guard = usvg::set_warning_handler(opt.usvg.warning_handler.as_ref());
+
+    let (view_box, size) = utils::image_view_box(tree, opt);
+    let (mut dt, img_view) = create_target(size, opt)?;
 
     // Fill background.
-    if let Some(c) = opt.background {
-        dt.clear(raqote::SolidSource { r: c.red, g: c.green, b: c.blue, a: 255 });
+    if let Some(c) = opt.resolved_background() {
+        dt.clear(c.to_color().to_solid(c.alpha));
     }
 
-    render_to_canvas(tree, opt, img_view, &mut dt);
+    render_node_to_canvas(&tree.root(), opt, view_box, img_view, &mut dt);
 
-    Some(dt)
+    apply_offset(dt, opt)
 }
 
 /// Renders SVG to image.
@@ -158,6 +156,8 @@ pub fn render_node_to_image(
     node: &usvg::Node,
     opt: &Options,
 ) -> Option<raqote::DrawTarget> {
+    let _guard = usvg::set_warning_handler(opt.usvg.warning_handler.as_ref());
+
     let node_bbox = if let Some(bbox) = node.calculate_bbox() {
         bbox
     } else {
@@ -173,13 +173,31 @@ pub fn render_node_to_image(
     };
 
     // Fill background.
-    if let Some(c) = opt.background {
-        dt.clear(raqote::SolidSource { r: c.red, g: c.green, b: c.blue, a: 255 });
+    if let Some(c) = opt.resolved_background() {
+        dt.clear(c.to_color().to_solid(c.alpha));
     }
 
     render_node_to_canvas(node, opt, vbox, img_size, &mut dt);
 
-    Some(dt)
+    apply_offset(dt, opt)
+}
+
+/// Renders a node with the specified ID to an image of the node's bounding box size.
+///
+/// Returns the image and the bounding box in the root element coordinates,
+/// which can be used to position the image. Useful to export icons from a sprite sheet.
+///
+/// Returns `None` when a renderable node with this ID doesn't exist
+/// or has a zero size, or when an image allocation failed.
+pub fn render_node_by_id_to_image(
+    tree: &usvg::Tree,
+    opt: &Options,
+    id: &str,
+) -> Option<(raqote::DrawTarget, Rect)> {
+    let node = tree.node_by_id(id)?;
+    let bbox = node.calculate_bbox()?;
+    let img = render_node_to_image(&node, opt)?;
+    Some((img, bbox))
 }
 
 /// Renders SVG to canvas.
@@ -192,6 +210,25 @@ pub fn render_to_canvas(
     render_node_to_canvas(&tree.root(), opt, tree.svg_node().view_box, img_size, dt);
 }
 
+/// Renders SVG to canvas, limited to the specified rect.
+///
+/// `rect` is in canvas pixels and is not affected by the current canvas transform.
+/// Pixels outside of it are left untouched, which is useful for partial repaints.
+pub fn render_rect_to_canvas(
+    tree: &usvg::Tree,
+    opt: &Options,
+    img_size: ScreenSize,
+    rect: ScreenRect,
+    dt: &mut raqote::DrawTarget,
+) {
+    dt.push_clip_rect(raqote::IntRect::new(
+        raqote::IntPoint::new(rect.x(), rect.y()),
+        raqote::IntPoint::new(rect.right(), rect.bottom()),
+    ));
+    render_to_canvas(tree, opt, img_size, dt);
+    dt.pop_clip();
+}
+
 /// Renders SVG node to canvas.
 pub fn render_node_to_canvas(
     node: &usvg::Node,
@@ -200,6 +237,8 @@ pub fn render_node_to_canvas(
     img_size: ScreenSize,
     dt: &mut raqote::DrawTarget,
 ) {
+    let _guard = usvg::set_warning_handler(opt.usvg.warning_handler.as_ref());
+
     render_node_to_canvas_impl(node, opt, view_box, img_size, &mut RenderState::Ok, dt)
 }
 
@@ -235,6 +274,31 @@ fn create_target(
     Some((dt, img_size))
 }
 
+/// Moves the image by `Options::offset`.
+fn apply_offset(
+    dt: raqote::DrawTarget,
+    opt: &Options,
+) -> Option<raqote::DrawTarget> {
+    let (dx, dy) = opt.offset;
+    if dx == 0 && dy == 0 {
+        return Some(dt);
+    }
+
+    let size = ScreenSize::new(dt.width() as u32, dt.height() as u32)?;
+    let size = utils::apply_offset(size, opt.offset)?;
+
+    let mut new_dt = raqote::DrawTarget::new(size.width() as i32, size.height() as i32);
+
+    // Fill background.
+    if let Some(c) = opt.resolved_background() {
+        new_dt.clear(c.to_color().to_solid(c.alpha));
+    }
+
+    new_dt.draw_image_at(dx as f32, dy as f32, &dt.as_image(), &raqote::DrawOptions::default());
+
+    Some(new_dt)
+}
+
 /// Applies viewbox transformation to the painter.
 fn apply_viewbox_transform(
     view_box: usvg::ViewBox,
@@ -257,6 +321,15 @@ fn render_node(
             render_group(node, opt, state, layers, dt)
         }
         usvg::NodeKind::Path(ref path) => {
+            let ts = usvg::Transform::from_native(dt.get_transform());
+            if crate::is_culled(path, ts, opt) {
+                return None;
+            }
+
+            let outline = crate::outline_path(path, opt);
+            let path = outline.as_ref().unwrap_or(path);
+            let snapped = crate::snap_path(path, ts, opt);
+            let path = snapped.as_ref().unwrap_or(path);
             path::draw(&node.tree(), path, opt, raqote::DrawOptions::default(), dt)
         }
         usvg::NodeKind::Image(ref img) => {
@@ -483,3 +556,132 @@ fn clear_subsurface(dt: &mut raqote::DrawTarget) {
     dt.set_transform(&raqote::Transform::identity());
     dt.make_transparent();
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_with_mapped_colors() {
+        let svg = "
+        <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+            <rect width='100' height='100' fill='#ff00ff'/>
+        </svg>
+        ";
+
+        let opt = Options::default();
+        let mut tree = usvg::Tree::from_str(svg, &opt.usvg).unwrap();
+        tree.map_colors(|c| usvg::Color::new(255 - c.red, 255 - c.green, 255 - c.blue));
+        let dt = render_to_image(&tree, &opt).unwrap();
+        assert_eq!(dt.get_data()[50 * 100 + 50], 0xff00ff00);
+    }
+
+    #[test]
+    fn render_rect_to_canvas_keeps_outside() {
+        let svg = "
+        <svg viewBox='0 0 50 50' xmlns='http://www.w3.org/2000/svg'>
+            <rect width='50' height='50' fill='#00ff00'/>
+        </svg>
+        ";
+
+        let opt = Options::default();
+        let tree = usvg::Tree::from_str(svg, &opt.usvg).unwrap();
+
+        // Render at a device pixel ratio of 2 into an existing image.
+        let mut dt = raqote::DrawTarget::new(100, 100);
+        dt.clear(raqote::SolidSource { r: 255, g: 0, b: 0, a: 255 });
+        dt.set_transform(&raqote::Transform::create_scale(2.0, 2.0));
+
+        let img_size = ScreenSize::new(50, 50).unwrap();
+        let rect = ScreenRect::new(20, 30, 40, 10).unwrap();
+        render_rect_to_canvas(&tree, &opt, img_size, rect, &mut dt);
+
+        // The rect is in device pixels.
+        let data = dt.get_data();
+        for &(x, y) in &[(20, 30), (59, 30), (40, 35), (59, 39)] {
+            assert_eq!(data[y * 100 + x], 0xff00ff00, "{} {}", x, y);
+        }
+        for &(x, y) in &[(19, 35), (60, 35), (40, 29), (40, 40), (90, 90)] {
+            assert_eq!(data[y * 100 + x], 0xffff0000, "{} {}", x, y);
+        }
+
+        // The canvas state is restored.
+        assert_eq!(*dt.get_transform(), raqote::Transform::create_scale(2.0, 2.0));
+    }
+
+    #[test]
+    fn render_chunks() {
+        let svg = "
+        <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+            <radialGradient id='rg1'>
+                <stop offset='0' stop-color='yellow'/>
+                <stop offset='1' stop-color='blue'/>
+            </radialGradient>
+            <rect width='60' height='60' fill='url(#rg1)'/>
+            <circle cx='50' cy='50' r='30' fill='green' opacity='0.5'/>
+            <rect x='40' y='40' width='60' height='60' fill='url(#rg1)' stroke='black'/>
+        </svg>
+        ";
+
+        let opt = Options::default();
+        let tree = usvg::Tree::from_str(svg, &opt.usvg).unwrap();
+        let img = render_to_image(&tree, &opt).unwrap();
+
+        let size = ScreenSize::new(img.width() as u32, img.height() as u32).unwrap();
+        let mut dt = raqote::DrawTarget::new(img.width(), img.height());
+        let mut count = 0;
+        usvg::Tree::from_str_chunked(svg, &opt.usvg, |tree| {
+            render_to_canvas(tree, &opt, size, &mut dt);
+            count += 1;
+        }).unwrap();
+
+        assert_eq!(count, 3);
+        assert_eq!(dt.get_data(), img.get_data());
+    }
+
+    #[test]
+    fn optimize() {
+        // An editor-like output with nested groups, duplicated gradients and hidden layers.
+        let svg = "
+        <svg width='100' height='100' xmlns='http://www.w3.org/2000/svg'>
+            <defs>
+                <linearGradient id='lg1' x1='0.0000001' x2='1.0000001'>
+                    <stop offset='0' stop-color='#ff0000'/>
+                    <stop offset='1' stop-color='#0000ff'/>
+                </linearGradient>
+                <linearGradient id='lg2' x1='0.0000001' x2='1.0000001'>
+                    <stop offset='0' stop-color='#ff0000'/>
+                    <stop offset='1' stop-color='#0000ff'/>
+                </linearGradient>
+                <linearGradient id='lg3'/>
+            </defs>
+            <g id='layer1'>
+                <g>
+                    <rect x='10.0000012' y='10.0000034' width='40.0000056' height='40' fill='url(#lg1)'/>
+                </g>
+                <g>
+                    <g>
+                        <circle cx='70.0000021' cy='70.0000045' r='20.0000011' fill='url(#lg2)'/>
+                    </g>
+                </g>
+            </g>
+            <g id='layer2' visibility='hidden'>
+                <rect width='100' height='100' fill='green'/>
+            </g>
+            <path d='M 10 90 L 90 90' fill='none'/>
+        </svg>
+        ";
+
+        let opt = Options::default();
+        let optimized = usvg::optimize(svg, &opt.usvg, &usvg::OptimizeOptions::default()).unwrap();
+
+        let tree1 = usvg::Tree::from_str(svg, &opt.usvg).unwrap();
+        let tree2 = usvg::Tree::from_str(&optimized, &opt.usvg).unwrap();
+        assert!(tree2.root().descendants().count() < tree1.root().descendants().count());
+
+        let img1 = render_to_image(&tree1, &opt).unwrap();
+        let img2 = render_to_image(&tree2, &opt).unwrap();
+        assert_eq!(img1.get_data(), img2.get_data());
+    }
+}
@@ -67,6 +67,9 @@ pub fn stroke(
     dt: &mut raqote::DrawTarget,
 ) {
     if let Some(ref stroke) = stroke {
+        let ts = usvg::Transform::from_native(dt.get_transform());
+        let stroke = &*stroke.resolve_non_scaling(ts);
+
         let cap = match stroke.linecap {
             usvg::LineCap::Butt => raqote::LineCap::Butt,
             usvg::LineCap::Round => raqote::LineCap::Round,
@@ -156,8 +159,8 @@ fn prepare_linear<'a>(
     );
 
     if let raqote::Source::LinearGradient(_, _, ref mut transform) = grad {
-        let ts: raqote::Transform = ts.to_native();
-        if let Some(ts) = ts.inverse() {
+        if let Some(ts) = ts.invert() {
+            let ts: raqote::Transform = ts.to_native();
             *transform = transform.pre_transform(&ts);
         }
     }
@@ -199,8 +202,8 @@ fn prepare_radial<'a>(
     match grad {
           raqote::Source::RadialGradient(_, _, ref mut transform)
         | raqote::Source::TwoCircleRadialGradient(_, _, _, _, _, _, ref mut transform) => {
-            let ts: raqote::Transform = ts.to_native();
-            if let Some(ts) = ts.inverse() {
+            if let Some(ts) = ts.invert() {
+                let ts: raqote::Transform = ts.to_native();
                 *transform = transform.pre_transform(&ts);
             }
         }
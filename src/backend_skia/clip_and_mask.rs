@@ -140,7 +140,8 @@ pub fn mask(
         mask_surface.restore();
     }
 
-    {
+    // An alpha mask uses the alpha channel as is.
+    if mask.kind == usvg::MaskType::Luminance {
         use rgb::FromSlice;
         use std::mem::swap;
 
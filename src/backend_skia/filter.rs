@@ -7,7 +7,6 @@ use std::rc::Rc;
 
 use crate::skia;
 use rgb::FromSlice;
-use log::warn;
 use usvg::ColorInterpolation as ColorSpace;
 
 use crate::prelude::*;
@@ -386,7 +385,7 @@ impl Filter<skia::Surface> for SkiaFilter {
                     super::image::draw_svg(data, view_box, opt, &mut buffer);
                 } else {
                     super::image::draw_raster(
-                        format, data, view_box, fe.rendering_mode, opt, &mut buffer,
+                        format, data, view_box, fe.rendering_mode, &mut buffer,
                     );
                 }
             }
@@ -21,7 +21,7 @@ pub fn draw(
     if image.format == usvg::ImageFormat::SVG {
         draw_svg(&image.data, image.view_box, opt, canvas);
     } else {
-        draw_raster(image.format, &image.data, image.view_box, image.rendering_mode, opt, canvas);
+        draw_raster(image.format, &image.data, image.view_box, image.rendering_mode, canvas);
     }
 
     image.view_box.rect
@@ -32,10 +32,9 @@ pub fn draw_raster(
     data: &usvg::ImageData,
     view_box: usvg::ViewBox,
     rendering_mode: usvg::ImageRendering,
-    opt: &Options,
     canvas: &mut skia::Canvas,
 ) {
-    let img = try_opt!(image::load_raster(format, data, opt));
+    let img = try_opt!(image::load_raster(format, data));
 
     let image = {
         let (w, h) = img.size.dimensions();
@@ -5,7 +5,6 @@
 //! Skia backend implementation.
 
 use crate::skia;
-use log::warn;
 
 use crate::{prelude::*, layers, ConvTransform, RenderState};
 
@@ -97,9 +96,12 @@ pub fn render_to_image(
     tree: &usvg::Tree,
     opt: &Options,
 ) -> Option<skia::Surface> {
-    let (mut img, img_size) = create_root_image(tree.svg_node().size.to_screen_size(), opt)?;
-    render_to_canvas(tree, opt, img_size, &mut img);
-    Some(img)
+    let _guard = usvg::set_warning_handler(opt.usvg.warning_handler.as_ref());
+
+    let (view_box, size) = utils::image_view_box(tree, opt);
+    let (mut img, img_size) = create_root_image(size, opt)?;
+    render_node_to_canvas(&tree.root(), opt, view_box, img_size, &mut img);
+    apply_offset(img, opt)
 }
 
 /// Renders SVG node to image.
@@ -107,6 +109,8 @@ pub fn render_node_to_image(
     node: &usvg::Node,
     opt: &Options,
 ) -> Option<skia::Surface> {
+    let _guard = usvg::set_warning_handler(opt.usvg.warning_handler.as_ref());
+
     let node_bbox = if let Some(bbox) = node.calculate_bbox() {
         bbox
     } else {
@@ -122,7 +126,25 @@ pub fn render_node_to_image(
     let (mut img, img_size) = create_root_image(node_bbox.size().to_screen_size(), opt)?;
 
     render_node_to_canvas(node, opt, vbox, img_size, &mut img);
-    Some(img)
+    apply_offset(img, opt)
+}
+
+/// Renders a node with the specified ID to an image of the node's bounding box size.
+///
+/// Returns the image and the bounding box in the root element coordinates,
+/// which can be used to position the image. Useful to export icons from a sprite sheet.
+///
+/// Returns `None` when a renderable node with this ID doesn't exist
+/// or has a zero size, or when an image allocation failed.
+pub fn render_node_by_id_to_image(
+    tree: &usvg::Tree,
+    opt: &Options,
+    id: &str,
+) -> Option<(skia::Surface, Rect)> {
+    let node = tree.node_by_id(id)?;
+    let bbox = node.calculate_bbox()?;
+    let img = render_node_to_image(&node, opt)?;
+    Some((img, bbox))
 }
 
 /// Renders SVG to canvas.
@@ -135,6 +157,30 @@ pub fn render_to_canvas(
     render_node_to_canvas(&tree.root(), opt, tree.svg_node().view_box, img_size, canvas);
 }
 
+/// Renders SVG to canvas, limited to the specified rect.
+///
+/// `rect` is in canvas pixels and is not affected by the current canvas transform.
+/// Pixels outside of it are left untouched, which is useful for partial repaints.
+pub fn render_rect_to_canvas(
+    tree: &usvg::Tree,
+    opt: &Options,
+    img_size: ScreenSize,
+    rect: ScreenRect,
+    canvas: &mut skia::Canvas,
+) {
+    canvas.save();
+
+    let ts = canvas.get_matrix();
+    canvas.reset_matrix();
+    canvas.set_clip_rect(rect.x() as f64, rect.y() as f64,
+                         rect.width() as f64, rect.height() as f64);
+    canvas.set_matrix(&ts);
+
+    render_to_canvas(tree, opt, img_size, canvas);
+
+    canvas.restore();
+}
+
 /// Renders SVG node to canvas.
 pub fn render_node_to_canvas(
     node: &usvg::Node,
@@ -143,6 +189,8 @@ pub fn render_node_to_canvas(
     img_size: ScreenSize,
     canvas: &mut skia::Canvas,
 ) {
+    let _guard = usvg::set_warning_handler(opt.usvg.warning_handler.as_ref());
+
     render_node_to_canvas_impl(node, opt, view_box, img_size, &mut RenderState::Ok, canvas)
 }
 
@@ -177,8 +225,8 @@ fn create_root_image(
     let mut img = try_create_surface!(img_size, None);
 
     // Fill background.
-    if let Some(c) = opt.background {
-        img.fill(c.red, c.green, c.blue, 255);
+    if let Some(c) = opt.resolved_background() {
+        img.fill(c.red, c.green, c.blue, c.alpha);
     } else {
         img.fill(0, 0, 0, 0);
     }
@@ -186,6 +234,35 @@ fn create_root_image(
     Some((img, img_size))
 }
 
+/// Moves the image by `Options::offset`.
+fn apply_offset(
+    img: skia::Surface,
+    opt: &Options,
+) -> Option<skia::Surface> {
+    let (dx, dy) = opt.offset;
+    if dx == 0 && dy == 0 {
+        return Some(img);
+    }
+
+    let size = ScreenSize::new(img.width(), img.height())?;
+    let size = utils::apply_offset(size, opt.offset)?;
+
+    let mut new_img = try_create_surface!(size, None);
+
+    // Fill background.
+    if let Some(c) = opt.resolved_background() {
+        new_img.fill(c.red, c.green, c.blue, c.alpha);
+    } else {
+        new_img.fill(0, 0, 0, 0);
+    }
+
+    new_img.draw_surface(
+        &img, dx as f64, dy as f64, 255, skia::BlendMode::SourceOver, skia::FilterQuality::Low,
+    );
+
+    Some(new_img)
+}
+
 /// Applies viewbox transformation to the painter.
 fn apply_viewbox_transform(
     view_box: usvg::ViewBox,
@@ -208,6 +285,15 @@ fn render_node(
             render_group(node, opt, state, layers, canvas)
         }
         usvg::NodeKind::Path(ref path) => {
+            let ts = usvg::Transform::from_native(&canvas.get_matrix());
+            if crate::is_culled(path, ts, opt) {
+                return None;
+            }
+
+            let outline = crate::outline_path(path, opt);
+            let path = outline.as_ref().unwrap_or(path);
+            let snapped = crate::snap_path(path, ts, opt);
+            let path = snapped.as_ref().unwrap_or(path);
             path::draw(&node.tree(), path, opt, skia::BlendMode::SourceOver, canvas)
         }
         usvg::NodeKind::Image(ref img) => {
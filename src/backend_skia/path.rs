@@ -37,18 +37,33 @@ pub fn draw(
 
     let global_ts = usvg::Transform::from_native(&canvas.get_matrix());
 
-    if path.fill.is_some() {
-        let mut fill = style::fill(tree, &path.fill, opt, style_bbox, global_ts);
-        fill.set_anti_alias(antialias);
-        fill.set_blend_mode(blend_mode);
-        canvas.draw_path(&skia_path, &fill);
-    }
+    let draw_fill = |canvas: &mut skia::Canvas| {
+        if path.fill.is_some() {
+            let mut fill = style::fill(tree, &path.fill, opt, style_bbox, global_ts);
+            fill.set_anti_alias(antialias);
+            fill.set_blend_mode(blend_mode);
+            canvas.draw_path(&skia_path, &fill);
+        }
+    };
+
+    let draw_stroke = |canvas: &mut skia::Canvas| {
+        if path.stroke.is_some() {
+            let mut stroke = style::stroke(tree, &path.stroke, opt, style_bbox, global_ts);
+            stroke.set_anti_alias(antialias);
+            stroke.set_blend_mode(blend_mode);
+            canvas.draw_path(&skia_path, &stroke);
+        }
+    };
 
-    if path.stroke.is_some() {
-        let mut stroke = style::stroke(tree, &path.stroke, opt, style_bbox, global_ts);
-        stroke.set_anti_alias(antialias);
-        stroke.set_blend_mode(blend_mode);
-        canvas.draw_path(&skia_path, &stroke);
+    match path.paint_order {
+        usvg::PaintOrder::FillAndStroke => {
+            draw_fill(canvas);
+            draw_stroke(canvas);
+        }
+        usvg::PaintOrder::StrokeAndFill => {
+            draw_stroke(canvas);
+            draw_fill(canvas);
+        }
     }
 
     bbox
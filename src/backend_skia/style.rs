@@ -56,6 +56,7 @@ pub fn stroke(
     paint.set_style(skia::PaintStyle::Stroke);
 
     if let Some(ref stroke) = stroke {
+        let stroke = &*stroke.resolve_non_scaling(global_ts);
         let opacity = stroke.opacity;
         match stroke.paint {
             usvg::Paint::Color(c) => {
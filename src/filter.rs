@@ -4,7 +4,6 @@
 
 use std::rc::Rc;
 
-use log::warn;
 use usvg::ColorInterpolation as ColorSpace;
 
 use crate::prelude::*;
@@ -204,6 +203,9 @@ pub trait Filter<T: ImageExt> {
                     let input = Self::get_input(&fe.input, region, inputs, &results)?;
                     Self::apply_offset(fe, filter.primitive_units, bbox, ts, input)
                 }
+                usvg::FilterKind::FePassThrough(ref fe) => {
+                    Self::get_input(&fe.input, region, inputs, &results)
+                }
                 usvg::FilterKind::FeComposite(ref fe) => {
                     let input1 = Self::get_input(&fe.input1, region, inputs, &results)?;
                     let input2 = Self::get_input(&fe.input2, region, inputs, &results)?;
@@ -472,8 +474,6 @@ pub fn calc_region<T: ImageExt>(
     ts: &usvg::Transform,
     canvas: &T,
 ) -> Result<ScreenRect, Error> {
-    let path = usvg::PathData::from_rect(filter.rect);
-
     let region_ts = if filter.units == usvg::Units::ObjectBoundingBox {
         let bbox = bbox.ok_or(Error::InvalidRegion)?;
         let bbox_ts = usvg::Transform::from_bbox(bbox);
@@ -485,7 +485,7 @@ pub fn calc_region<T: ImageExt>(
     };
 
     let canvas_rect = ScreenRect::new(0, 0, canvas.width(), canvas.height()).unwrap();
-    let region = path.bbox_with_transform(region_ts, None)
+    let region = region_ts.map_rect(filter.rect)
         .ok_or_else(|| Error::InvalidRegion)?
         .to_screen_rect()
         .fit_to_rect(canvas_rect);
@@ -708,12 +708,12 @@ pub fn transform_light_source(
 
             let (x, y) = ts.apply(light.x, light.y);
             light.x = x - region.x() as f64;
-            light.y = y - region.x() as f64;
+            light.y = y - region.y() as f64;
             light.z = light.z * sz;
 
             let (x, y) = ts.apply(light.points_at_x, light.points_at_y);
             light.points_at_x = x - region.x() as f64;
-            light.points_at_y = y - region.x() as f64;
+            light.points_at_y = y - region.y() as f64;
             light.points_at_z = light.points_at_z * sz;
         }
     }
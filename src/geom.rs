@@ -6,7 +6,7 @@
 
 use std::{cmp, f64, fmt};
 
-use usvg;
+use usvg::{self, TransformExt, TransformFromBBox};
 pub use usvg::{Rect, Size};
 
 
@@ -138,7 +138,7 @@ pub trait RectExt: Sized {
 
     /// Transforms the `Rect` using the provided `Transform`.
     ///
-    /// This method is expensive.
+    /// Returns a bounding box of the transformed rect.
     fn transform(&self, ts: &usvg::Transform) -> Option<Self>;
 
     /// Returns rect's size in screen units.
@@ -150,35 +150,13 @@ pub trait RectExt: Sized {
 
 impl RectExt for Rect {
     fn bbox_transform(&self, bbox: Rect) -> Self {
-        let x = self.x() * bbox.width() + bbox.x();
-        let y = self.y() * bbox.height() + bbox.y();
-        let w = self.width() * bbox.width();
-        let h = self.height() * bbox.height();
-        Rect::new(x, y, w, h).unwrap()
+        // Can't fail, because both rects have a non-zero size.
+        usvg::Transform::from_bbox(bbox).map_rect(*self).unwrap()
     }
 
+    #[inline]
     fn transform(&self, ts: &usvg::Transform) -> Option<Self> {
-        if !ts.is_default() {
-            let path = &[
-                usvg::PathSegment::MoveTo {
-                    x: self.x(), y: self.y()
-                },
-                usvg::PathSegment::LineTo {
-                    x: self.right(), y: self.y()
-                },
-                usvg::PathSegment::LineTo {
-                    x: self.right(), y: self.bottom()
-                },
-                usvg::PathSegment::LineTo {
-                    x: self.x(), y: self.bottom()
-                },
-                usvg::PathSegment::ClosePath,
-            ];
-
-            usvg::SubPathData(path).bbox_with_transform(*ts, None)
-        } else {
-            Some(*self)
-        }
+        ts.map_rect(*self)
     }
 
     #[inline]
@@ -2,9 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::{fs, path};
-
-use log::warn;
+use std::fs;
 
 use crate::prelude::*;
 
@@ -22,14 +20,12 @@ pub enum ImageData {
 pub fn load_raster(
     format: usvg::ImageFormat,
     data: &usvg::ImageData,
-    opt: &Options,
 ) -> Option<Image> {
-    let img = _load_raster(format, data, opt);
+    let img = _load_raster(format, data);
 
     if img.is_none() {
         match data {
             usvg::ImageData::Path(ref path) => {
-                let path = get_abs_path(path, opt);
                 warn!("Failed to load an external image: {:?}.", path);
             }
             usvg::ImageData::Raw(_) => {
@@ -44,13 +40,11 @@ pub fn load_raster(
 fn _load_raster(
     format: usvg::ImageFormat,
     data: &usvg::ImageData,
-    opt: &Options,
 ) -> Option<Image> {
     debug_assert!(format != usvg::ImageFormat::SVG);
 
     match data {
         usvg::ImageData::Path(ref path) => {
-            let path = get_abs_path(path, opt);
             let data = fs::read(path).ok()?;
 
             if format == usvg::ImageFormat::JPEG {
@@ -152,21 +146,35 @@ pub fn load_sub_svg(
         usvg: usvg::Options {
             path: None,
             dpi: opt.usvg.dpi,
+            dpi_x: opt.usvg.dpi_x,
+            dpi_y: opt.usvg.dpi_y,
             font_family: opt.usvg.font_family.clone(),
             font_size: opt.usvg.font_size,
+            default_stroke_width: opt.usvg.default_stroke_width,
+            container_size: None,
             languages: opt.usvg.languages.clone(),
             shape_rendering: opt.usvg.shape_rendering,
             text_rendering: opt.usvg.text_rendering,
             image_rendering: opt.usvg.image_rendering,
             keep_named_groups: false,
+            keep_image_data: opt.usvg.keep_image_data,
+            foreign_object_placeholder: opt.usvg.foreign_object_placeholder,
+            animation_time: opt.usvg.animation_time,
+            warning_handler: opt.usvg.warning_handler.clone(),
+            font_db: opt.usvg.font_db.clone(),
         },
         fit_to: FitTo::Original,
         background: None,
+        default_background: None,
+        offset: (0, 0),
+        outline_mode: opt.outline_mode,
+        pixel_snap: opt.pixel_snap,
+        cull_threshold: opt.cull_threshold,
+        clip_to_viewbox: opt.clip_to_viewbox,
     };
 
     let tree = match data {
         usvg::ImageData::Path(ref path) => {
-            let path = get_abs_path(path, opt);
             sub_opt.usvg.path = Some(path.clone());
             usvg::Tree::from_file(path, &sub_opt.usvg).ok()?
         }
@@ -254,13 +262,3 @@ pub fn image_rect(
 
     new_size.to_size().to_rect(x, y)
 }
-
-fn get_abs_path(
-    rel_path: &path::Path,
-    opt: &Options,
-) -> path::PathBuf {
-    match opt.usvg.path {
-        Some(ref path) => path.parent().unwrap().join(rel_path),
-        None => rel_path.into(),
-    }
-}
@@ -12,6 +12,9 @@ In simple terms: no animations and scripting.
 
 It can be used as a simple SVG to PNG converted.
 And as an embeddable library to paint SVG on an application native canvas.
+
+All backends are optional. Without them, only the SVG preprocessing
+into a `usvg::Tree` is available, which is enough for analysis tools.
 */
 
 #![doc(html_root_url = "https://docs.rs/resvg/0.9.0")]
@@ -19,6 +22,24 @@ And as an embeddable library to paint SVG on an application native canvas.
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
+// Rendering helpers are used only by backends.
+#![cfg_attr(
+    not(any(
+        feature = "cairo-backend",
+        feature = "qt-backend",
+        feature = "skia-backend",
+        feature = "raqote-backend",
+    )),
+    allow(dead_code, unused_macros)
+)]
+
+/// Emits a warning via `usvg::emit_warning`.
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        usvg::emit_warning(format_args!($($arg)+))
+    };
+}
+
 /// Unwraps `Option` and invokes `return` on `None`.
 macro_rules! try_opt {
     ($task:expr) => {
@@ -46,7 +67,7 @@ macro_rules! try_opt_warn {
         match $task {
             Some(v) => v,
             None => {
-                log::warn!($msg);
+                warn!($msg);
                 return;
             }
         }
@@ -55,7 +76,7 @@ macro_rules! try_opt_warn {
         match $task {
             Some(v) => v,
             None => {
-                log::warn!($fmt, $($arg)*);
+                warn!($fmt, $($arg)*);
                 return;
             }
         }
@@ -69,7 +90,7 @@ macro_rules! try_opt_warn_or {
         match $task {
             Some(v) => v,
             None => {
-                log::warn!($msg);
+                warn!($msg);
                 return $ret;
             }
         }
@@ -78,7 +99,7 @@ macro_rules! try_opt_warn_or {
         match $task {
             Some(v) => v,
             None => {
-                log::warn!($fmt, $($arg)*);
+                warn!($fmt, $($arg)*);
                 return $ret;
             }
         }
@@ -177,6 +198,7 @@ pub trait OutputImage {
 ///
 /// - If both backends are enabled - cairo backend will be returned.
 /// - If no backends are enabled - will panic.
+///   Use `usvg::Tree` directly in this case.
 /// - Otherwise will return a corresponding backend.
 #[allow(unreachable_code)]
 pub fn default_backend() -> Box<dyn Render> {
@@ -213,6 +235,111 @@ pub(crate) fn use_shape_antialiasing(
     }
 }
 
+/// Returns a copy of the `path` with `Options::outline_mode` applied.
+///
+/// Returns `None` when the outline mode is disabled or has an invalid width.
+pub(crate) fn outline_path(
+    path: &usvg::Path,
+    opt: &Options,
+) -> Option<usvg::Path> {
+    use usvg::IsValidLength;
+
+    // Zero, negative and non-finite widths are ignored.
+    let width = opt.outline_mode.filter(|w| w.is_finite() && w.is_valid_length())?;
+
+    Some(usvg::Path {
+        fill: None,
+        stroke: Some(usvg::Stroke {
+            paint: usvg::Paint::Color(usvg::Color::black()),
+            width: width.into(),
+            ..usvg::Stroke::default()
+        }),
+        ..path.clone()
+    })
+}
+
+/// Checks that the `path` should be skipped according to `Options::cull_threshold`.
+///
+/// `ts` is the current canvas transform.
+pub(crate) fn is_culled(
+    path: &usvg::Path,
+    ts: usvg::Transform,
+    opt: &Options,
+) -> bool {
+    let threshold = match opt.cull_threshold {
+        Some(v) => v,
+        None => return false,
+    };
+
+    match path.data.bbox_with_transform(ts, path.stroke.as_ref()) {
+        Some(bbox) => bbox.width() < threshold && bbox.height() < threshold,
+        None => false,
+    }
+}
+
+/// Returns a copy of the `path` with `Options::pixel_snap` applied.
+///
+/// `ts` is the current canvas transform.
+///
+/// Returns `None` when the pixel snapping is disabled or `ts` is not invertible.
+pub(crate) fn snap_path(
+    path: &usvg::Path,
+    ts: usvg::Transform,
+    opt: &Options,
+) -> Option<usvg::Path> {
+    use usvg::TransformExt;
+
+    if !opt.pixel_snap {
+        return None;
+    }
+
+    let inv_ts = ts.invert()?;
+
+    // A stroke with an odd width has to be centered on a pixel to cover whole pixels.
+    let mut offset = 0.0;
+    if let (None, Some(stroke)) = (&path.fill, &path.stroke) {
+        let stroke = stroke.resolve_non_scaling(ts);
+        let (sx, sy) = ts.get_scale();
+        let width = (stroke.width.value() * (sx * sy).sqrt()).round();
+        if width as i64 % 2 == 1 {
+            offset = 0.5;
+        }
+    }
+
+    let snap = |x: f64, y: f64| {
+        let (x, y) = ts.apply(x, y);
+        inv_ts.apply((x - offset).round() + offset, (y - offset).round() + offset)
+    };
+
+    let mut data = Vec::with_capacity(path.data.len());
+    for seg in path.data.iter() {
+        data.push(match *seg {
+            usvg::PathSegment::MoveTo { x, y } => {
+                let (x, y) = snap(x, y);
+                usvg::PathSegment::MoveTo { x, y }
+            }
+            usvg::PathSegment::LineTo { x, y } => {
+                let (x, y) = snap(x, y);
+                usvg::PathSegment::LineTo { x, y }
+            }
+            usvg::PathSegment::CurveTo { x1, y1, x2, y2, x, y } => {
+                let (x1, y1) = snap(x1, y1);
+                let (x2, y2) = snap(x2, y2);
+                let (x, y) = snap(x, y);
+                usvg::PathSegment::CurveTo { x1, y1, x2, y2, x, y }
+            }
+            usvg::PathSegment::ClosePath => {
+                usvg::PathSegment::ClosePath
+            }
+        });
+    }
+
+    Some(usvg::Path {
+        data: std::rc::Rc::new(usvg::PathData(data)),
+        ..path.clone()
+    })
+}
+
 /// Converts an image to an alpha mask.
 pub(crate) fn image_to_mask(
     data: &mut [rgb::alt::BGRA8],
@@ -283,3 +410,68 @@ pub(crate) fn filter_background_start_node(
     // Skip the current element.
     parent.ancestors().skip(1).find(|node| has_enable_background(node))
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_without_backend() {
+        let svg = "
+        <svg viewBox='0 0 100 50' xmlns='http://www.w3.org/2000/svg'>
+            <rect width='100' height='50' fill='green'/>
+        </svg>
+        ";
+
+        let opt = Options::default();
+        let tree = usvg::Tree::from_str(svg, &opt.usvg).unwrap();
+        assert_eq!(tree.svg_node().size.to_screen_size(), ScreenSize::new(100, 50).unwrap());
+        let paths = tree.root().descendants()
+            .filter(|n| matches!(*n.borrow(), usvg::NodeKind::Path(_)))
+            .count();
+        assert_eq!(paths, 1);
+    }
+
+    #[test]
+    fn image_view_box_without_content() {
+        use usvg::FuzzyEq;
+
+        let svg = "
+        <svg viewBox='0 0 100 50' xmlns='http://www.w3.org/2000/svg'>
+            <g id='g1'/>
+        </svg>
+        ";
+
+        let opt = Options { clip_to_viewbox: false, ..Options::default() };
+        let tree = usvg::Tree::from_str(svg, &opt.usvg).unwrap();
+        let (view_box, size) = utils::image_view_box(&tree, &opt);
+        assert!(view_box.rect.fuzzy_eq(&tree.svg_node().view_box.rect));
+        assert_eq!(size, ScreenSize::new(100, 50).unwrap());
+    }
+
+    #[test]
+    fn root_transform() {
+        let svg = "
+        <svg width='200' height='100' viewBox='50 50 100 100' xmlns='http://www.w3.org/2000/svg'/>
+        ";
+
+        let opt = Options { fit_to: FitTo::Zoom(2.0), offset: (10, 20), ..Options::default() };
+        let tree = usvg::Tree::from_str(svg, &opt.usvg).unwrap();
+        let ts = utils::root_transform(&tree, &opt).unwrap();
+        // The viewBox center.
+        assert_eq!(ts.apply(100.0, 100.0), (210.0, 120.0));
+
+        // Without the viewBox clip, the transform matches the enlarged image.
+        let svg = "
+        <svg width='100' height='100' viewBox='0 0 50 50' xmlns='http://www.w3.org/2000/svg'>
+            <rect x='-25' y='20' width='100' height='10'/>
+        </svg>
+        ";
+
+        let opt = Options { clip_to_viewbox: false, ..Options::default() };
+        let tree = usvg::Tree::from_str(svg, &opt.usvg).unwrap();
+        let ts = utils::root_transform(&tree, &opt).unwrap();
+        assert_eq!(ts.apply(0.0, 0.0), (50.0, 0.0));
+    }
+}
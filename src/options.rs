@@ -13,7 +13,16 @@ pub enum FitTo {
     /// Scale to height.
     Height(u32),
     /// Zoom by factor.
+    ///
+    /// Can be used as a device pixel ratio for HiDPI output,
+    /// since filters are applied in device pixels as well.
     Zoom(f32),
+    /// Fit into a box of the given size.
+    ///
+    /// The image will have exactly this size and the content will be positioned
+    /// according to the root element's `preserveAspectRatio`,
+    /// which is centered and aspect-preserving by default.
+    Size(u32, u32),
 }
 
 /// Rendering options.
@@ -29,9 +38,77 @@ pub struct Options {
     /// An image background color.
     ///
     /// Sets an image background color. Does not affect rendering to canvas.
+    /// Unlike element fills, the background has no separate opacity,
+    /// so the alpha is stored in the color itself.
+    ///
+    /// `None` equals to `default_background`.
+    pub background: Option<usvg::ColorA>,
+
+    /// A base image background color.
+    ///
+    /// Used when `background` is not set, so an application can set a base color once
+    /// and override it for a specific render via `background`.
     ///
     /// `None` equals to transparent.
-    pub background: Option<usvg::Color>,
+    pub default_background: Option<usvg::ColorA>,
+
+    /// Moves the rendered image by the specified amount of pixels.
+    ///
+    /// The output image is enlarged by the offset, so the image itself
+    /// is never cropped. The freed area is filled with the `background`.
+    ///
+    /// Applied after `fit_to`. Does not affect rendering to canvas.
+    pub offset: (u32, u32),
+
+    /// Renders all paths as outlines.
+    ///
+    /// When set, the fill of every path will be removed and the stroke
+    /// will be replaced with a solid black one of the specified width.
+    /// The width is in user units.
+    ///
+    /// Clip paths are not affected.
+    /// Useful for debugging the shapes geometry.
+    ///
+    /// Does not modify the `usvg::Tree`.
+    pub outline_mode: Option<f64>,
+
+    /// Snaps path coordinates to the device pixel grid.
+    ///
+    /// Makes axis-aligned shapes crisp at the cost of a slight geometry distortion.
+    /// Coordinates are snapped to pixel edges, except for paths that are only stroked
+    /// with an odd device width, which are snapped to pixel centers instead.
+    ///
+    /// Text is converted to paths, so it is snapped as well.
+    /// Images and clip paths are not affected.
+    pub pixel_snap: bool,
+
+    /// Skips paths that are smaller than the specified amount of device pixels.
+    ///
+    /// A path is skipped when both its width and height, including the stroke
+    /// and after the current transform, are below the threshold.
+    /// Speeds up the rendering of documents with a lot of tiny shapes,
+    /// but changes the output, therefore disabled by default.
+    ///
+    /// Clip paths are not affected.
+    pub cull_threshold: Option<f64>,
+
+    /// Clips the image to the root viewport.
+    ///
+    /// When disabled, the image is enlarged to include the content
+    /// outside of the root viewport, keeping the original scale.
+    ///
+    /// Enabled by default, as required by the SVG spec.
+    /// Does not affect rendering to canvas.
+    pub clip_to_viewbox: bool,
+}
+
+impl Options {
+    /// Returns the resolved image background color.
+    ///
+    /// `background` takes precedence over `default_background`.
+    pub fn resolved_background(&self) -> Option<usvg::ColorA> {
+        self.background.or(self.default_background)
+    }
 }
 
 impl Default for Options {
@@ -40,6 +117,12 @@ impl Default for Options {
             usvg: usvg::Options::default(),
             fit_to: FitTo::Original,
             background: None,
+            default_background: None,
+            offset: (0, 0),
+            outline_mode: None,
+            pixel_snap: false,
+            cull_threshold: None,
+            clip_to_viewbox: true,
         }
     }
 }
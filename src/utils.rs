@@ -22,20 +22,107 @@ pub(crate) fn fit_to(
             Some(size)
         }
         FitTo::Width(w) => {
-            let h = (w as f64 * sizef.height() / sizef.width()).ceil();
+            let h = sizef.scale_to_width(w as f64)?.height().ceil();
             ScreenSize::new(w, h as u32)
         }
         FitTo::Height(h) => {
-            let w = (h as f64 * sizef.width() / sizef.height()).ceil();
+            let w = sizef.scale_to_height(h as f64)?.width().ceil();
             ScreenSize::new(w as u32, h)
         }
         FitTo::Zoom(z) => {
             Size::new(sizef.width() * z as f64, sizef.height() * z as f64)
                  .map(|s| s.to_screen_size())
         }
+        FitTo::Size(w, h) => {
+            ScreenSize::new(w, h)
+        }
     }
 }
 
+/// Returns the view box and the size of the rendered image before `Options::fit_to`.
+///
+/// When `Options::clip_to_viewbox` is disabled, the root viewport is enlarged
+/// to include the whole content.
+pub(crate) fn image_view_box(
+    tree: &usvg::Tree,
+    opt: &Options,
+) -> (usvg::ViewBox, ScreenSize) {
+    let svg = tree.svg_node();
+    let original = (svg.view_box, svg.size.to_screen_size());
+    if opt.clip_to_viewbox {
+        return original;
+    }
+
+    // A tree without drawable content can return an empty bbox.
+    let bbox = match tree.root().calculate_bbox() {
+        Some(bbox) if !bbox.fuzzy_eq(&Rect::new_bbox()) => bbox,
+        _ => return original,
+    };
+
+    // The root viewport in the user space.
+    let vb = svg.view_box;
+    let ts = match checked_view_box_to_transform(vb.rect, vb.aspect, svg.size) {
+        Some(ts) => ts,
+        None => return original,
+    };
+    let viewport = match ts.invert().and_then(|ts| ts.map_rect(svg.size.to_rect(0.0, 0.0))) {
+        Some(r) => r,
+        None => return original,
+    };
+
+    let rect = viewport.expand(bbox);
+    if rect.fuzzy_eq(&viewport) {
+        return original;
+    }
+
+    let (sx, sy) = ts.get_scale();
+    let size = match Size::new(rect.width() * sx, rect.height() * sy) {
+        Some(size) => size.to_screen_size(),
+        None => return original,
+    };
+
+    let view_box = usvg::ViewBox {
+        rect,
+        aspect: usvg::AspectRatio {
+            defer: false,
+            align: usvg::Align::None,
+            slice: false,
+        },
+    };
+
+    (view_box, size)
+}
+
+/// Returns a transform from the root element coordinates to the rendered image pixels.
+///
+/// Includes `Options::fit_to`, `Options::clip_to_viewbox`, the `viewBox` mapping
+/// and `Options::offset`.
+/// This is the same transform that is used by `render_to_image` of all backends.
+///
+/// Returns `None` when the image size is invalid.
+pub fn root_transform(
+    tree: &usvg::Tree,
+    opt: &Options,
+) -> Option<usvg::Transform> {
+    let (view_box, size) = image_view_box(tree, opt);
+    let img_size = fit_to(size, opt.fit_to)?;
+
+    let mut ts = usvg::Transform::new_translate(opt.offset.0 as f64, opt.offset.1 as f64);
+    ts.append(&view_box_to_transform(view_box.rect, view_box.aspect, img_size.to_size()));
+    Some(ts)
+}
+
+/// Returns `size` enlarged by `offset`.
+pub(crate) fn apply_offset(
+    size: ScreenSize,
+    offset: (u32, u32),
+) -> Option<ScreenSize> {
+    ScreenSize::new(
+        size.width().checked_add(offset.0)?,
+        size.height().checked_add(offset.1)?,
+    )
+}
+
 pub(crate) fn apply_view_box(
     vb: &usvg::ViewBox,
     img_size: ScreenSize,
@@ -45,11 +132,38 @@ pub(crate) fn apply_view_box(
     if vb.aspect.align == usvg::Align::None {
         s
     } else {
-        if vb.aspect.slice {
-            img_size.expand_to(s)
-        } else {
-            img_size.scale_to(s)
-        }
+        let size = img_size.to_size().fit_within(s.to_size(), vb.aspect.slice);
+        // Can't fail, because the size is valid, so both sides are at least 1 after `ceil`.
+        ScreenSize::new(size.width().ceil() as u32, size.height().ceil() as u32).unwrap()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view_box_size(w: f64, h: f64, slice: bool, img_size: (u32, u32)) -> (u32, u32) {
+        let vb = usvg::ViewBox {
+            rect: Rect::new(0.0, 0.0, w, h).unwrap(),
+            aspect: usvg::AspectRatio { defer: false, align: usvg::Align::XMidYMid, slice },
+        };
+
+        let s = apply_view_box(&vb, ScreenSize::new(img_size.0, img_size.1).unwrap());
+        (s.width(), s.height())
+    }
+
+    #[test]
+    fn apply_view_box_meet() {
+        assert_eq!(view_box_size(100.0, 100.0, false, (160, 90)), (100, 57));
+        // Must not overflow the view box because of the rounding.
+        assert_eq!(view_box_size(10.0, 11.0, false, (9, 10)), (10, 11));
+    }
+
+    #[test]
+    fn apply_view_box_slice() {
+        assert_eq!(view_box_size(100.0, 100.0, true, (160, 90)), (178, 100));
+        assert_eq!(view_box_size(10.0, 11.0, true, (9, 10)), (10, 12));
     }
 }
 
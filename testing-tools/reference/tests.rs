@@ -0,0 +1,190 @@
+//! Reference image tests.
+//!
+//! Each test renders an SVG from the `svg` directory using the default backend
+//! and compares the result with a PNG from the `<backend>/` directory.
+//! There are no references for the Qt and Skia backends yet, so the tests are ignored
+//! for them. Use `--ignored` to run them anyway.
+//!
+//! Set `RESVG_UPDATE_REFERENCES=1` to overwrite the references with the current output.
+
+#![cfg(any(
+    feature = "cairo-backend",
+    feature = "qt-backend",
+    feature = "skia-backend",
+    feature = "raqote-backend",
+))]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use resvg::prelude::*;
+
+// Must be in sync with `resvg::default_backend`.
+const BACKEND: &str = if cfg!(feature = "cairo-backend") {
+    "cairo"
+} else if cfg!(feature = "qt-backend") {
+    "qt"
+} else if cfg!(feature = "skia-backend") {
+    "skia"
+} else {
+    "raqote"
+};
+
+// Backends and the PNG encoder can round premultiplied colors differently.
+const TOLERANCE: i32 = 2;
+
+macro_rules! test {
+    ($(#[$attr:meta])* $name:ident, $file:expr) => {
+        test!($(#[$attr])* $name, $file, |_| {});
+    };
+    ($(#[$attr:meta])* $name:ident, $file:expr, $setup:expr) => {
+        #[test]
+        // No references for the Qt and Skia backends. See `BACKEND`.
+        #[cfg_attr(
+            all(not(feature = "cairo-backend"), any(feature = "qt-backend", feature = "skia-backend")),
+            ignore
+        )]
+        $(#[$attr])*
+        fn $name() {
+            check(stringify!($name), $file, None, $setup);
+        }
+    };
+}
+
+macro_rules! test_node {
+    ($name:ident, $file:expr, $id:expr, $setup:expr) => {
+        #[test]
+        // No references for the Qt and Skia backends. See `BACKEND`.
+        #[cfg_attr(
+            all(not(feature = "cairo-backend"), any(feature = "qt-backend", feature = "skia-backend")),
+            ignore
+        )]
+        fn $name() {
+            check(stringify!($name), $file, Some($id), $setup);
+        }
+    };
+}
+
+test!(fe_spot_light_in_offset_region, "fe-spot-light-in-offset-region");
+test!(render_with_offset, "render-with-offset", |opt| opt.offset = (10, 20));
+test!(pattern_with_view_box_and_overflow, "pattern-with-view-box-and-overflow");
+test!(stroke_under_fill, "stroke-under-fill");
+test!(outline_mode, "outline-mode", |opt| opt.outline_mode = Some(2.0));
+test!(fe_blend_multiply, "fe-blend-multiply");
+test!(mask_type, "mask-type");
+test!(image_with_transform, "image-with-transform");
+test!(fe_source_alpha, "fe-source-alpha");
+test!(filter_with_zoom, "filter-with-zoom", |opt| opt.fit_to = FitTo::Zoom(2.0));
+test!(opacity_on_fill_and_stroke, "opacity-on-fill-and-stroke");
+test!(polygon_closing_join, "polygon-closing-join");
+test!(#[cfg(feature = "text")] text_without_antialiasing, "text-without-antialiasing", |opt| {
+    opt.usvg.text_rendering = usvg::TextRendering::OptimizeSpeed;
+});
+test!(pattern_in_user_space_on_use, "pattern-in-user-space-on-use");
+test!(clip_path_with_clip_path, "clip-path-with-clip-path");
+test!(fractional_view_box_offset, "fractional-view-box-offset");
+test!(fit_to_size, "fit-to-size", |opt| opt.fit_to = FitTo::Size(128, 128));
+test!(fe_tile_with_subregions, "fe-tile-with-subregions");
+test!(pixel_snap, "pixel-snap", |opt| opt.pixel_snap = true);
+test!(cull_threshold, "cull-threshold", |opt| opt.cull_threshold = Some(2.0));
+test!(filter_color_interpolation, "filter-color-interpolation");
+test!(no_viewbox_clip, "no-viewbox-clip", |opt| opt.clip_to_viewbox = false);
+test!(fe_convolve_matrix_sharpen, "fe-convolve-matrix-sharpen");
+test!(clip_path_and_mask, "clip-path-and-mask");
+test!(#[cfg(feature = "text")] clip_path_with_text, "clip-path-with-text");
+test!(non_scaling_stroke_dashes, "non-scaling-stroke-dashes", |opt| {
+    opt.fit_to = FitTo::Zoom(3.0);
+});
+test!(default_background, "default-background", |opt| {
+    opt.default_background = Some(usvg::ColorA::new(255, 0, 0, 255));
+});
+test!(default_background_with_background, "default-background", |opt| {
+    opt.background = Some(usvg::ColorA::new(255, 255, 255, 255));
+    opt.default_background = Some(usvg::ColorA::new(255, 0, 0, 255));
+});
+test!(default_background_without_viewbox_clip, "default-background", |opt| {
+    opt.default_background = Some(usvg::ColorA::new(255, 0, 0, 255));
+    opt.clip_to_viewbox = false;
+});
+test_node!(default_background_on_node, "default-background", "circle1", |opt| {
+    opt.default_background = Some(usvg::ColorA::new(255, 0, 0, 255));
+});
+test!(container_size, "container-size", |opt| {
+    opt.usvg.container_size = usvg::Size::new(160.0, 120.0);
+});
+test_node!(render_node_by_id, "render-node-by-id", "icon2", |opt| {
+    opt.usvg.keep_named_groups = true;
+});
+
+fn check(name: &str, file: &str, id: Option<&str>, setup: fn(&mut Options)) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testing-tools/reference");
+    let backend_dir = dir.join(BACKEND);
+    let update = std::env::var_os("RESVG_UPDATE_REFERENCES").is_some();
+
+    let mut opt = Options::default();
+    opt.usvg.font_db.load_fonts_dir(dir.parent().unwrap().join("fonts"));
+    setup(&mut opt);
+
+    let path = dir.join("svg").join(file).with_extension("svg");
+    let tree = usvg::Tree::from_file(&path, &opt.usvg).unwrap();
+
+    let backend = resvg::default_backend();
+    let mut img = match id {
+        Some(id) => {
+            let node = tree.node_by_id(id).unwrap();
+            backend.render_node_to_image(&node, &opt).unwrap()
+        }
+        None => backend.render_to_image(&tree, &opt).unwrap(),
+    };
+
+    let png_path = backend_dir.join(name.replace('_', "-")).with_extension("png");
+    if update {
+        fs::create_dir_all(&backend_dir).unwrap();
+        assert!(img.save_png(&png_path));
+        return;
+    }
+
+    let expected = load_png(&png_path);
+    let actual = img.make_rgba_vec();
+    assert_eq!(actual.len(), expected.len(), "image size mismatch");
+
+    let mut diff = 0;
+    for (a, b) in actual.chunks(4).zip(expected.chunks(4)) {
+        if !is_close(a, b) {
+            diff += 1;
+        }
+    }
+
+    if diff != 0 {
+        let out = save_actual(name, &mut *img);
+        panic!("{} pixels differ from {:?}, the output was saved to {:?}", diff, png_path, out);
+    }
+}
+
+/// Compares premultiplied colors, because the color of a transparent pixel is undefined.
+fn is_close(a: &[u8], b: &[u8]) -> bool {
+    let premultiply = |c: &[u8], i: usize| c[i] as i32 * c[3] as i32 / 255;
+    (0..3).all(|i| (premultiply(a, i) - premultiply(b, i)).abs() <= TOLERANCE)
+        && (a[3] as i32 - b[3] as i32).abs() <= TOLERANCE
+}
+
+/// Loads an RGBA PNG.
+fn load_png(path: &Path) -> Vec<u8> {
+    let file = fs::File::open(path)
+        .unwrap_or_else(|_| panic!("{:?} is missing, run with RESVG_UPDATE_REFERENCES=1", path));
+    let (info, mut reader) = png::Decoder::new(file).read_info().unwrap();
+    assert_eq!(info.color_type, png::ColorType::RGBA);
+    assert_eq!(info.bit_depth, png::BitDepth::Eight);
+
+    let mut data = vec![0; info.buffer_size()];
+    reader.next_frame(&mut data).unwrap();
+    data
+}
+
+fn save_actual(name: &str, img: &mut dyn OutputImage) -> PathBuf {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("target/reference");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{}-{}.png", BACKEND, name.replace('_', "-")));
+    img.save_png(&path);
+    path
+}
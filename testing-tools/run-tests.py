@@ -190,3 +190,6 @@ if 'USVG_TESTING' in os.environ:
         run(['cargo', 'build', '--no-default-features'], check=True)
         # test with the `text` feature
         run(['cargo', 'test'], check=True)
+
+    # resvg must be buildable without any backend
+    run(['cargo', 'test', '--no-default-features'], check=True)
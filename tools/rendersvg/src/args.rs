@@ -36,7 +36,8 @@ OPTIONS:
                                 [default: 96] [possible values: 10..4000]
 
         --background COLOR      Sets the background color.
-                                Examples: red, #fff, #fff000
+                                Examples: red, #fff, #fff000,
+                                'rgba(255, 0, 0, 0.5)'
         --font-family FAMILY    Sets the default font family
                                 [default: 'Times New Roman']
         --font-size SIZE        Sets the default font size
@@ -82,8 +83,8 @@ struct CliArgs {
     width: Option<u32>,
     height: Option<u32>,
     zoom: Option<f32>,
-    dpi: u32,
-    background: Option<usvg::Color>,
+    dpi: f64,
+    background: Option<usvg::ColorA>,
     font_family: String,
     font_size: u32,
     languages: Vec<String>,
@@ -108,7 +109,7 @@ fn collect_args() -> Result<CliArgs, pico_args::Error> {
         width:              input.value_from_fn(["-w", "--width"], parse_length)?,
         height:             input.value_from_fn(["-h", "--height"], parse_length)?,
         zoom:               input.value_from_fn(["-z", "--zoom"], parse_zoom)?,
-        dpi:                input.value_from_fn("--dpi", parse_dpi)?.unwrap_or(96),
+        dpi:                input.value_from_fn("--dpi", parse_dpi)?.unwrap_or(96.0),
         background:         input.value_from_str("--background")?,
         font_family:        input.value_from_str("--font-family")?
                                  .unwrap_or_else(|| "Times New Roman".to_string()),
@@ -128,10 +129,10 @@ fn collect_args() -> Result<CliArgs, pico_args::Error> {
     })
 }
 
-fn parse_dpi(s: &str) -> Result<u32, String> {
-    let n: u32 = s.parse().map_err(|_| "invalid number")?;
+fn parse_dpi(s: &str) -> Result<f64, String> {
+    let n: f64 = s.parse().map_err(|_| "invalid number")?;
 
-    if n >= 10 && n <= 4000 {
+    if n >= 10.0 && n <= 4000.0 {
         Ok(n)
     } else {
         Err("DPI out of bounds".to_string())
@@ -251,17 +252,32 @@ pub fn parse() -> Result<(Args, resvg::Options), String> {
     let opt = resvg::Options {
         usvg: usvg::Options {
             path: Some(in_svg.into()),
-            dpi: args.dpi as f64,
+            dpi: args.dpi,
+            dpi_x: None,
+            dpi_y: None,
             font_family: args.font_family.clone(),
             font_size: args.font_size as f64,
+            default_stroke_width: 1.0,
+            container_size: None,
             languages: args.languages,
             shape_rendering: args.shape_rendering,
             text_rendering: args.text_rendering,
             image_rendering: args.image_rendering,
             keep_named_groups,
+            keep_image_data: false,
+            foreign_object_placeholder: false,
+            animation_time: None,
+            warning_handler: None,
+            font_db: usvg::FontDatabase::new(),
         },
         fit_to,
         background: args.background,
+        default_background: None,
+        offset: (0, 0),
+        outline_mode: None,
+        pixel_snap: false,
+        cull_threshold: None,
+        clip_to_viewbox: true,
     };
 
     Ok((app_args, opt))
@@ -4,7 +4,7 @@
 
 use std::fmt;
 use std::fs::File;
-use std::io::{self, Read, Write};
+use std::io::{self, Read};
 use std::path::Path;
 use std::process;
 
@@ -26,6 +26,9 @@ OPTIONS:
     -V, --version               Prints version information
     -c                          Prints the output SVG to the stdout
         --keep-named-groups     Disables removing of groups with non-empty ID
+        --foreign-object-placeholder
+                                Renders 'foreignObject' elements as light gray
+                                boxes instead of skipping them
         --dpi DPI               Sets the resolution
                                 [default: 96] [possible values: 10..4000]
         --font-family FAMILY    Sets the default font family
@@ -49,6 +52,8 @@ OPTIONS:
                                 [default: optimizeQuality]
                                 [possible values: optimizeQuality,
                                 optimizeSpeed]
+        --animation-time SEC    Evaluates SMIL animations at the specified time
+                                in seconds. Animations are ignored otherwise
         --indent INDENT         Sets the XML nodes indent
                                 [values: none, 0, 1, 2, 3, 4, tabs] [default: 4]
         --attrs-indent INDENT   Sets the XML attributes indent
@@ -67,13 +72,15 @@ struct Args {
     version: bool,
     stdout: bool,
     keep_named_groups: bool,
-    dpi: u32,
+    foreign_object_placeholder: bool,
+    dpi: f64,
     font_family: String,
     font_size: u32,
     languages: Vec<String>,
     shape_rendering: usvg::ShapeRendering,
     text_rendering: usvg::TextRendering,
     image_rendering: usvg::ImageRendering,
+    animation_time: Option<f64>,
     indent: usvg::XmlIndent,
     attrs_indent: usvg::XmlIndent,
     quiet: bool,
@@ -87,7 +94,8 @@ fn collect_args() -> Result<Args, pico_args::Error> {
         version:            input.contains(["-V", "--version"]),
         stdout:             input.contains("-c"),
         keep_named_groups:  input.contains("--keep-named-groups"),
-        dpi:                input.value_from_fn("--dpi", parse_dpi)?.unwrap_or(96),
+        foreign_object_placeholder: input.contains("--foreign-object-placeholder"),
+        dpi:                input.value_from_fn("--dpi", parse_dpi)?.unwrap_or(96.0),
         font_family:        input.value_from_str("--font-family")?
                                  .unwrap_or_else(|| "Times New Roman".to_string()),
         font_size:          input.value_from_fn("--font-size", parse_font_size)?.unwrap_or(12),
@@ -96,6 +104,7 @@ fn collect_args() -> Result<Args, pico_args::Error> {
         shape_rendering:    input.value_from_str("--shape-rendering")?.unwrap_or_default(),
         text_rendering:     input.value_from_str("--text-rendering")?.unwrap_or_default(),
         image_rendering:    input.value_from_str("--image-rendering")?.unwrap_or_default(),
+        animation_time:     input.value_from_fn("--animation-time", parse_animation_time)?,
         indent:             input.value_from_fn("--indent", parse_indent)?
                                  .unwrap_or(usvg::XmlIndent::Spaces(4)),
         attrs_indent:       input.value_from_fn("--attrs-indent", parse_indent)?
@@ -105,10 +114,10 @@ fn collect_args() -> Result<Args, pico_args::Error> {
     })
 }
 
-fn parse_dpi(s: &str) -> Result<u32, String> {
-    let n: u32 = s.parse().map_err(|_| "invalid number")?;
+fn parse_dpi(s: &str) -> Result<f64, String> {
+    let n: f64 = s.parse().map_err(|_| "invalid number")?;
 
-    if n >= 10 && n <= 4000 {
+    if (10.0..=4000.0).contains(&n) {
         Ok(n)
     } else {
         Err("DPI out of bounds".to_string())
@@ -125,6 +134,16 @@ fn parse_font_size(s: &str) -> Result<u32, String> {
     }
 }
 
+fn parse_animation_time(s: &str) -> Result<f64, String> {
+    let n: f64 = s.parse().map_err(|_| "invalid number")?;
+
+    if n >= 0.0 && n.is_finite() {
+        Ok(n)
+    } else {
+        Err("animation time cannot be negative".to_string())
+    }
+}
+
 fn parse_languages(s: &str) -> Result<Vec<String>, String> {
     let mut langs = Vec::new();
     for lang in s.split(',') {
@@ -234,14 +253,23 @@ fn process(args: &Args) -> Result<(), String> {
             InputFrom::Stdin => None,
             InputFrom::File(ref f) => Some(f.into()),
         },
-        dpi: args.dpi as f64,
+        dpi: args.dpi,
+        dpi_x: None,
+        dpi_y: None,
         font_family: args.font_family.clone(),
         font_size: args.font_size as f64,
+        default_stroke_width: 1.0,
+        container_size: None,
         languages: args.languages.clone(),
         shape_rendering: args.shape_rendering,
         text_rendering: args.text_rendering,
         image_rendering: args.image_rendering,
         keep_named_groups: args.keep_named_groups,
+        keep_image_data: false,
+        foreign_object_placeholder: args.foreign_object_placeholder,
+        animation_time: args.animation_time,
+        warning_handler: None,
+        font_db: usvg::FontDatabase::new(),
     };
 
     let input_str = match in_svg {
@@ -259,17 +287,15 @@ fn process(args: &Args) -> Result<(), String> {
         attributes_indent: args.attrs_indent,
     };
 
-    let s = tree.to_string(xml_opt);
     match out_svg {
         OutputTo::Stdout => {
-            io::stdout()
-                .write_all(s.as_bytes())
+            tree.write_to(io::stdout().lock(), xml_opt)
                 .map_err(|_| format!("failed to write to the stdout"))?;
         }
         OutputTo::File(path) => {
-            let mut f = File::create(path)
+            let f = File::create(path)
                 .map_err(|_| format!("failed to create the output file"))?;
-            f.write_all(s.as_bytes())
+            tree.write_to(io::BufWriter::new(f), xml_opt)
                 .map_err(|_| format!("failed to write to the output file"))?;
         }
     }
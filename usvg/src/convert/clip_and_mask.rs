@@ -2,7 +2,9 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::tree;
+use std::rc::Rc;
+
+use crate::tree::{self, prelude::*};
 use crate::svgtree;
 use super::prelude::*;
 
@@ -109,6 +111,7 @@ pub fn convert_mask(
         units,
         content_units,
         rect,
+        kind: node.attribute(AId::MaskType).unwrap_or_default(),
         mask,
     }));
 
@@ -122,3 +125,224 @@ pub fn convert_mask(
         None
     }
 }
+
+
+/// A CSS basic shape set via the `clip-path` property.
+enum BasicShape {
+    Circle {
+        r: ShapeRadius,
+        cx: Length,
+        cy: Length,
+    },
+    Inset {
+        top: Length,
+        right: Length,
+        bottom: Length,
+        left: Length,
+    },
+}
+
+enum ShapeRadius {
+    Length(Length),
+    ClosestSide,
+    FarthestSide,
+}
+
+/// Checks that the element's `clip-path` is a CSS basic shape and not a link.
+pub fn has_basic_shape_clip(node: svgtree::Node) -> bool {
+    node.attribute::<&str>(AId::ClipPath).map(|v| v != "none").unwrap_or(false)
+}
+
+/// Converts a CSS basic shape `clip-path` into a `clipPath` element.
+///
+/// Only `circle()` and `inset()` are supported. Percentages are resolved against
+/// the element's bounding box including the stroke, aka `stroke-box`,
+/// which is the default reference box for SVG elements.
+pub fn convert_basic_shape_clip(
+    node: svgtree::Node,
+    group: &mut tree::Node,
+    tree: &mut tree::Tree,
+) {
+    let value: &str = try_opt!(node.attribute(AId::ClipPath));
+    let shape = try_opt_warn!(
+        parse_basic_shape(value),
+        "Failed to parse clip-path value: '{}'.", value
+    );
+
+    let mut bbox = None;
+    for child in group.children() {
+        if let Some(r) = tree::calc_node_bbox(&child, tree::Transform::default()) {
+            bbox = Some(bbox.map_or(r, |bbox: Rect| bbox.expand(r)));
+        }
+    }
+    let bbox = try_opt!(bbox);
+
+    let resolve = |length: Length, base: f64| -> Option<f64> {
+        match length.unit {
+            Unit::None | Unit::Px => Some(length.num),
+            Unit::Percent => Some(base * length.num / 100.0),
+            _ => None,
+        }
+    };
+
+    let path = match shape {
+        BasicShape::Circle { r, cx, cy } => {
+            let cx = try_opt_warn!(resolve(cx, bbox.width()), "Unsupported clip-path units.");
+            let cy = try_opt_warn!(resolve(cy, bbox.height()), "Unsupported clip-path units.");
+            let cx = bbox.x() + cx;
+            let cy = bbox.y() + cy;
+
+            let sides = [cx - bbox.left(), bbox.right() - cx, cy - bbox.top(), bbox.bottom() - cy];
+            let r = match r {
+                ShapeRadius::Length(r) => {
+                    let base = (bbox.width().powi(2) + bbox.height().powi(2)).sqrt()
+                             / 2.0f64.sqrt();
+                    try_opt_warn!(resolve(r, base), "Unsupported clip-path units.")
+                }
+                ShapeRadius::ClosestSide => sides.iter().fold(f64::MAX, |a, b| a.min(b.abs())),
+                ShapeRadius::FarthestSide => sides.iter().fold(0.0f64, |a, b| a.max(b.abs())),
+            };
+
+            if r.is_valid_length() {
+                Some(super::shapes::ellipse_to_path(cx, cy, r, r))
+            } else {
+                None
+            }
+        }
+        BasicShape::Inset { top, right, bottom, left } => {
+            let (w, h) = (bbox.width(), bbox.height());
+            let top = try_opt_warn!(resolve(top, h), "Unsupported clip-path units.");
+            let right = try_opt_warn!(resolve(right, w), "Unsupported clip-path units.");
+            let bottom = try_opt_warn!(resolve(bottom, h), "Unsupported clip-path units.");
+            let left = try_opt_warn!(resolve(left, w), "Unsupported clip-path units.");
+
+            Rect::new(bbox.x() + left, bbox.y() + top, w - left - right, h - top - bottom)
+                .map(tree::PathData::from_rect)
+        }
+    };
+
+    let path = match path {
+        Some(path) => path,
+        None => {
+            // An empty clip path hides the whole element.
+            for mut child in group.children().collect::<Vec<_>>() {
+                child.detach();
+            }
+
+            return;
+        }
+    };
+
+    let id = super::use_node::gen_clip_path_id(node, tree);
+    let mut clip_path = tree.append_to_defs(tree::NodeKind::ClipPath(tree::ClipPath {
+        id: id.clone(),
+        ..tree::ClipPath::default()
+    }));
+
+    clip_path.append_kind(tree::NodeKind::Path(tree::Path {
+        fill: Some(tree::Fill::default()),
+        data: Rc::new(path),
+        ..tree::Path::default()
+    }));
+
+    if let tree::NodeKind::Group(ref mut g) = *group.borrow_mut() {
+        g.clip_path = Some(id);
+    }
+}
+
+fn parse_basic_shape(value: &str) -> Option<BasicShape> {
+    let mut s = svgtypes::Stream::from(value);
+    s.skip_spaces();
+    let name = s.consume_ident();
+    s.consume_byte(b'(').ok()?;
+    s.skip_spaces();
+
+    let shape = match name {
+        "circle" => {
+            let r = if s.starts_with(b"closest-side") {
+                s.advance(12);
+                ShapeRadius::ClosestSide
+            } else if s.starts_with(b"farthest-side") {
+                s.advance(13);
+                ShapeRadius::FarthestSide
+            } else if s.starts_with(b"at") || s.is_curr_byte_eq(b')') {
+                ShapeRadius::ClosestSide
+            } else {
+                ShapeRadius::Length(s.parse_length().ok()?)
+            };
+
+            s.skip_spaces();
+            let (cx, cy) = if s.starts_with(b"at") {
+                s.advance(2);
+                parse_position(&mut s)?
+            } else {
+                (Length::new(50.0, Unit::Percent), Length::new(50.0, Unit::Percent))
+            };
+
+            BasicShape::Circle { r, cx, cy }
+        }
+        "inset" => {
+            let mut list = Vec::new();
+            while !s.at_end() && !s.is_curr_byte_eq(b')') && list.len() < 4 {
+                list.push(s.parse_length().ok()?);
+                s.skip_spaces();
+            }
+
+            // The same rules as for the CSS `margin` shorthand.
+            let (top, right, bottom, left) = match *list.as_slice() {
+                [v] => (v, v, v, v),
+                [v, h] => (v, h, v, h),
+                [t, h, b] => (t, h, b, h),
+                [t, r, b, l] => (t, r, b, l),
+                _ => return None,
+            };
+
+            BasicShape::Inset { top, right, bottom, left }
+        }
+        _ => return None,
+    };
+
+    s.skip_spaces();
+    s.consume_byte(b')').ok()?;
+    s.skip_spaces();
+    if !s.at_end() {
+        return None;
+    }
+
+    Some(shape)
+}
+
+/// Parses a simplified CSS `<position>`: one or two keywords or lengths.
+fn parse_position(s: &mut svgtypes::Stream) -> Option<(Length, Length)> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Axis { X, Y, Any }
+
+    let mut list = Vec::new();
+    loop {
+        s.skip_spaces();
+        if s.at_end() || s.is_curr_byte_eq(b')') || list.len() == 2 {
+            break;
+        }
+
+        let item = match s.consume_ident() {
+            "left" => (Axis::X, Length::new(0.0, Unit::Percent)),
+            "right" => (Axis::X, Length::new(100.0, Unit::Percent)),
+            "top" => (Axis::Y, Length::new(0.0, Unit::Percent)),
+            "bottom" => (Axis::Y, Length::new(100.0, Unit::Percent)),
+            "center" => (Axis::Any, Length::new(50.0, Unit::Percent)),
+            "" => (Axis::Any, s.parse_length().ok()?),
+            _ => return None,
+        };
+        list.push(item);
+    }
+
+    let center = (Axis::Any, Length::new(50.0, Unit::Percent));
+    match *list.as_slice() {
+        [(Axis::Y, y)] => Some((center.1, y)),
+        [(_, x)] => Some((x, center.1)),
+        [(Axis::Y, y), (Axis::X, x)] | [(Axis::Y, y), (Axis::Any, x)] => Some((x, y)),
+        [(Axis::Y, _), (Axis::Y, _)] | [(Axis::X, _), (Axis::X, _)] => None,
+        [(_, x), (_, y)] => Some((x, y)),
+        _ => None,
+    }
+}
@@ -8,6 +8,7 @@ use crate::svgtree;
 use crate::tree;
 use super::prelude::*;
 use super::paint_server::{resolve_number, convert_units};
+use super::style;
 
 
 pub fn convert(
@@ -108,6 +109,10 @@ fn collect_children(
             EId::FeTurbulence => convert_fe_turbulence(child),
             EId::FeDiffuseLighting => convert_fe_diffuse_lighting(child, &primitives),
             EId::FeSpecularLighting => convert_fe_specular_lighting(child, &primitives),
+            EId::FeDropShadow => {
+                warn!("'{}' is not supported. Its input will be passed through.", EId::FeDropShadow);
+                convert_unsupported_primitive(child, &primitives)
+            }
             tag_name => {
                 warn!("'{}' is not a valid filter primitive. Skipped.", tag_name);
                 continue;
@@ -161,8 +166,16 @@ fn convert_fe_gaussian_blur(
         _ => (0.0, 0.0),
     };
 
-    if std_dev_x.is_sign_negative() { std_dev_x = 0.0; }
-    if std_dev_y.is_sign_negative() { std_dev_y = 0.0; }
+    // 'A negative value or a value of zero disables the effect
+    // of the given filter primitive.'
+    //
+    // A zero on only one axis is still valid and means that the blur
+    // should be applied only along the other axis.
+    // But a negative value on any axis disables the whole primitive.
+    if std_dev_x.is_sign_negative() || std_dev_y.is_sign_negative() {
+        std_dev_x = 0.0;
+        std_dev_y = 0.0;
+    }
 
     tree::FilterKind::FeGaussianBlur(tree::FeGaussianBlur {
         input: resolve_input(fe, AId::In, primitives),
@@ -183,6 +196,18 @@ fn convert_fe_offset(
     })
 }
 
+/// Converts an unsupported filter primitive into a pass through one.
+///
+/// Unlike skipping, this preserves the primitive `result`, which can be referenced later.
+fn convert_unsupported_primitive(
+    fe: svgtree::Node,
+    primitives: &[tree::FilterPrimitive],
+) -> tree::FilterKind {
+    tree::FilterKind::FePassThrough(tree::FePassThrough {
+        input: resolve_input(fe, AId::In, primitives),
+    })
+}
+
 fn convert_fe_blend(
     fe: svgtree::Node,
     primitives: &[tree::FilterPrimitive],
@@ -208,11 +233,12 @@ fn convert_fe_blend(
 fn convert_fe_flood(
     fe: svgtree::Node,
 ) -> tree::FilterKind {
-    let color = fe.attribute(AId::FloodColor).unwrap_or_else(tree::Color::black);
-    let opacity = fe.attribute(AId::FloodOpacity).unwrap_or_default();
+    let color = fe.attribute(AId::FloodColor)
+        .unwrap_or_else(|| tree::ColorA::from(tree::Color::black()));
+    let opacity: tree::Opacity = fe.attribute(AId::FloodOpacity).unwrap_or_default();
     tree::FilterKind::FeFlood(tree::FeFlood {
-        color,
-        opacity,
+        color: color.to_color(),
+        opacity: style::color_alpha(color) * opacity,
     })
 }
 
@@ -291,7 +317,7 @@ fn convert_fe_image(
         }
     };
 
-    let href = super::image::get_href_data(fe.element_id(), href, state.opt.path.as_ref());
+    let href = super::image::get_href_data(fe.element_id(), href, state.opt);
     let (img_data, format) = match href {
         Some((data, format)) => (data, format),
         None => return create_dummy_primitive(),
@@ -659,7 +685,7 @@ fn convert_lighting_color(
         Some(svgtree::AttributeValue::CurrentColor) => {
             node.find_attribute(AId::Color).unwrap_or_else(tree::Color::black)
         }
-        Some(svgtree::AttributeValue::Color(c)) => *c,
+        Some(svgtree::AttributeValue::Color(c)) => c.to_color(),
         _ => tree::Color::white(),
     }
 }
@@ -36,7 +36,7 @@ pub fn convert(
         "The 'image' element lacks the 'xlink:href' attribute. Skipped."
     );
 
-    let (data, format) = try_opt!(get_href_data(node.element_id(), href, state.opt.path.as_ref()));
+    let (data, format) = try_opt!(get_href_data(node.element_id(), href, state.opt));
     parent.append_kind(tree::NodeKind::Image(tree::Image {
         id: node.element_id().to_string(),
         transform: Default::default(),
@@ -51,7 +51,7 @@ pub fn convert(
 pub fn get_href_data(
     element_id: &str,
     href: &str,
-    path: Option<&path::PathBuf>,
+    opt: &Options,
 ) -> Option<(tree::ImageData, tree::ImageFormat)> {
     if let Ok(url) = data_url::DataUrl::process(href) {
         let (data, _) = url.decode_to_vec().ok()?;
@@ -68,14 +68,24 @@ pub fn get_href_data(
 
         Some((tree::ImageData::Raw(data), format))
     } else {
-        let path = match path {
-            Some(path) => path.parent()?.join(href),
+        let path = match opt.path {
+            Some(ref path) => path.parent()?.join(href),
             None => path::PathBuf::from(href),
         };
 
+        if opt.keep_image_data {
+            return match get_image_ext_format(&path) {
+                Some(format) => Some((tree::ImageData::Path(path), format)),
+                None => {
+                    warn!("'{}' doesn't have a PNG, JPEG or SVG(Z) extension.", href);
+                    None
+                }
+            };
+        }
+
         if path.exists() {
             if let Some(format) = get_image_file_format(&path) {
-                return Some((tree::ImageData::Path(path::PathBuf::from(href)), format));
+                return Some((tree::ImageData::Path(path), format));
             } else {
                 warn!("'{}' is not a PNG, JPEG or SVG(Z) image.", href);
             }
@@ -105,6 +115,16 @@ fn get_image_file_format(path: &path::Path) -> Option<tree::ImageFormat> {
     get_image_data_format(&d)
 }
 
+/// Detects an image format by the file extension.
+fn get_image_ext_format(path: &path::Path) -> Option<tree::ImageFormat> {
+    match utils::file_extension(path)?.to_lowercase().as_str() {
+        "png" => Some(tree::ImageFormat::PNG),
+        "jpg" | "jpeg" => Some(tree::ImageFormat::JPEG),
+        "svg" | "svgz" => Some(tree::ImageFormat::SVG),
+        _ => None,
+    }
+}
+
 /// Checks that file has a PNG or a JPEG magic bytes.
 fn get_image_data_format(data: &[u8]) -> Option<tree::ImageFormat> {
     if data.starts_with(b"\x89PNG\r\n\x1a\n") {
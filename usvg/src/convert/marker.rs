@@ -72,6 +72,8 @@ enum MarkerKind {
 
 enum MarkerOrientation {
     Auto,
+    /// Same as `Auto`, but the start marker is rotated by 180 degrees.
+    AutoStartReverse,
     Angle(f64),
 }
 
@@ -135,6 +137,10 @@ fn resolve(
 
         let angle = match convert_orientation(marker_node) {
             MarkerOrientation::Auto => calc_vertex_angle(path, idx),
+            MarkerOrientation::AutoStartReverse => {
+                let angle = calc_vertex_angle(path, idx);
+                if matches!(marker_kind, MarkerKind::Start) { angle + 180.0 } else { angle }
+            }
             MarkerOrientation::Angle(angle) => angle,
         };
 
@@ -143,8 +149,8 @@ fn resolve(
         }
 
         if let Some(vbox) = view_box {
-            let size = Size::new(r.width() * stroke_scale, r.height() * stroke_scale).unwrap();
-            let vbox_ts = utils::view_box_to_transform(vbox.rect, vbox.aspect, size);
+            let size = try_opt!(Size::new(r.width() * stroke_scale, r.height() * stroke_scale));
+            let vbox_ts = try_opt!(utils::checked_view_box_to_transform(vbox.rect, vbox.aspect, size));
             let (sx, sy) = vbox_ts.get_scale();
             ts.scale(sx, sy);
         } else {
@@ -180,7 +186,10 @@ fn stroke_scale(
 ) -> Option<f64> {
     match marker_node.attribute(AId::MarkerUnits) {
         Some("userSpaceOnUse") => Some(1.0),
-        _ => path_node.resolve_valid_length(AId::StrokeWidth, state, 1.0),
+        _ => {
+            let def = state.opt.default_stroke_width;
+            path_node.resolve_valid_length(AId::StrokeWidth, state, def)
+        }
     }
 }
 
@@ -469,22 +478,24 @@ fn convert_orientation(
 ) -> MarkerOrientation {
     use svgtypes::{Angle, AngleUnit};
 
-    if node.attribute(AId::Orient) == Some("auto") {
-        MarkerOrientation::Auto
-    } else {
-        match node.attribute::<Angle>(AId::Orient) {
-            Some(angle) => {
-                let a = match angle.unit {
-                    AngleUnit::Degrees  => angle.num,
-                    AngleUnit::Gradians => angle.num * 180.0 / 200.0,
-                    AngleUnit::Radians  => angle.num.to_degrees(),
-                };
+    match node.attribute(AId::Orient) {
+        Some("auto") => return MarkerOrientation::Auto,
+        Some("auto-start-reverse") => return MarkerOrientation::AutoStartReverse,
+        _ => {}
+    }
 
-                MarkerOrientation::Angle(a)
-            }
-            None => {
-                MarkerOrientation::Angle(0.0)
-            }
+    match node.attribute::<Angle>(AId::Orient) {
+        Some(angle) => {
+            let a = match angle.unit {
+                AngleUnit::Degrees  => angle.num,
+                AngleUnit::Gradians => angle.num * 180.0 / 200.0,
+                AngleUnit::Radians  => angle.num.to_degrees(),
+            };
+
+            MarkerOrientation::Angle(a)
+        }
+        None => {
+            MarkerOrientation::Angle(0.0)
         }
     }
 }
@@ -2,16 +2,9 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-#[cfg(feature = "text")]
-use std::cell::RefCell;
-#[cfg(feature = "text")]
-use std::rc::Rc;
-
 use svgtypes::Length;
 
 use crate::{svgtree, tree, tree::prelude::*, Error};
-#[cfg(feature = "text")]
-use crate::fontdb;
 
 mod clip_and_mask;
 mod filter;
@@ -21,12 +14,11 @@ mod paint_server;
 mod shapes;
 mod style;
 mod switch;
-mod units;
+pub(crate) mod units;
 mod use_node;
 #[cfg(feature = "text")] mod text;
 
 mod prelude {
-    pub use log::warn;
     pub use svgtypes::{FuzzyEq, FuzzyZero, Length};
     pub use crate::{geom::*, short::*, svgtree::{AId, EId}, Options, IsValidLength};
     pub use super::{SvgNodeExt, State};
@@ -38,15 +30,36 @@ use self::prelude::*;
 pub struct State<'a> {
     parent_clip_path: Option<svgtree::Node<'a>>,
     parent_marker: Option<svgtree::Node<'a>>,
+    /// A fill and a stroke of a shape that references the current marker.
+    ///
+    /// Used to resolve `context-fill` and `context-stroke`.
+    context_element: Option<(Option<tree::Fill>, Option<tree::Stroke>)>,
     fe_image_link: bool,
     size: Size,
     view_box: Rect,
     #[cfg(feature = "text")]
-    db: Rc<RefCell<fontdb::Database>>,
+    db: crate::FontDatabase,
     opt: &'a Options,
 }
 
 
+/// Groups post-processing passes. Can be disabled only by `optimize`.
+#[derive(Clone, Copy)]
+pub(crate) struct GroupPasses {
+    pub remove_empty_groups: bool,
+    pub ungroup_groups: bool,
+}
+
+impl Default for GroupPasses {
+    fn default() -> Self {
+        GroupPasses {
+            remove_empty_groups: true,
+            ungroup_groups: true,
+        }
+    }
+}
+
+
 /// Converts an input `Document` into a `Tree`.
 ///
 /// # Errors
@@ -56,6 +69,46 @@ pub struct State<'a> {
 pub fn convert_doc(
     svg_doc: &svgtree::Document,
     opt: &Options,
+) -> Result<tree::Tree, Error> {
+    convert_doc_with_passes(svg_doc, opt, GroupPasses::default())
+}
+
+/// Converts an input `Document` into a `Tree` using only the specified groups passes.
+pub(crate) fn convert_doc_with_passes(
+    svg_doc: &svgtree::Document,
+    opt: &Options,
+    passes: GroupPasses,
+) -> Result<tree::Tree, Error> {
+    let mut tree = convert_doc_impl(svg_doc, opt, passes, None)?;
+    remove_unused_defs(&mut tree);
+    Ok(tree)
+}
+
+/// Converts an input `Document` into a sequence of `Tree` chunks.
+///
+/// Each root `svg` child is converted separately and passed to `f`
+/// as a `Tree` that contains only this element and the `defs` collected so far.
+/// The element is removed from the tree afterwards, so the memory usage
+/// doesn't grow with the number of top-level elements.
+///
+/// When the root `svg` element itself requires a group (has `opacity`, `filter`, etc.),
+/// the whole document is passed as a single chunk.
+///
+/// Unlike `convert_doc`, unused `defs` are not removed, since they can be referenced
+/// by the following chunks.
+pub fn convert_doc_chunked<F: FnMut(&tree::Tree)>(
+    svg_doc: &svgtree::Document,
+    opt: &Options,
+    mut f: F,
+) -> Result<(), Error> {
+    convert_doc_impl(svg_doc, opt, GroupPasses::default(), Some(&mut f)).map(|_| ())
+}
+
+fn convert_doc_impl(
+    svg_doc: &svgtree::Document,
+    opt: &Options,
+    passes: GroupPasses,
+    on_chunk: Option<&mut dyn FnMut(&tree::Tree)>,
 ) -> Result<tree::Tree, Error> {
     let svg = svg_doc.root_element();
     let size = resolve_svg_size(&svg, opt)?;
@@ -64,8 +117,11 @@ pub fn convert_doc(
         aspect: svg.attribute(AId::PreserveAspectRatio).unwrap_or_default(),
     };
 
-    let svg_kind = tree::Svg { size, view_box };
-    let mut tree = tree::Tree::create(svg_kind);
+    let mut tree = tree::Tree::create(tree::Svg {
+        size,
+        view_box,
+        metadata: svg_doc.metadata().map(str::to_string),
+    });
 
     if !svg.is_visible_element(opt) {
         return Ok(tree);
@@ -74,24 +130,158 @@ pub fn convert_doc(
     let state = State {
         parent_clip_path: None,
         parent_marker: None,
+        context_element: None,
         fe_image_link: false,
         size,
         view_box: view_box.rect,
         #[cfg(feature = "text")]
-        db: Rc::new(RefCell::new(fontdb::Database::new())),
-        opt: &opt,
+        db: opt.font_db.clone(),
+        opt,
     };
 
-    convert_children(svg_doc.root(), &state, &mut tree.root(), &mut tree);
+    match on_chunk {
+        Some(f) if !is_group_required(svg) => {
+            for node in svg.children() {
+                convert_nodes(std::iter::once(node), svg_doc, &state, passes, &mut tree);
 
-    link_fe_image(svg_doc, &state, &mut tree);
-    remove_empty_groups(&mut tree);
-    ungroup_groups(opt, &mut tree);
-    remove_unused_defs(&mut tree);
+                let mut nodes: Vec<_> = tree.root().children()
+                    .filter(|n| !matches!(*n.borrow(), tree::NodeKind::Defs))
+                    .collect();
+
+                if !nodes.is_empty() {
+                    f(&tree);
+                }
+
+                for node in &mut nodes {
+                    node.detach();
+                }
+            }
+        }
+        on_chunk => {
+            convert_nodes(svg_doc.root().children(), svg_doc, &state, passes, &mut tree);
+
+            if let Some(f) = on_chunk {
+                f(&tree);
+            }
+        }
+    }
 
     Ok(tree)
 }
 
+/// Checks that the root `svg` element requires a group.
+///
+/// Uses the same conditions as `convert_group`, but checks only the element attributes,
+/// so nothing has to be converted. Links are not resolved, therefore a link
+/// to an invalid element requires a group as well, which disables the chunked conversion.
+fn is_group_required(svg: svgtree::Node) -> bool {
+    let opacity: tree::Opacity = svg.attribute(AId::Opacity).unwrap_or_default();
+    let transform: tree::Transform = svg.attribute(AId::Transform).unwrap_or_default();
+    let has_link = |aid| svg.has_attribute(aid) && svg.attribute(aid) != Some("none");
+
+       opacity.value().fuzzy_ne(&1.0)
+    || has_link(AId::ClipPath)
+    || has_link(AId::Mask)
+    || has_link(AId::Filter)
+    || !transform.is_default()
+    || svg.has_attribute(AId::EnableBackground)
+}
+
+/// Converts `nodes` into the tree root and post-processes them.
+///
+/// Only the `defs` created by this call are processed,
+/// so a chunked conversion doesn't have to check the same `defs` over and over again.
+fn convert_nodes<'a>(
+    nodes: impl Iterator<Item = svgtree::Node<'a>>,
+    svg_doc: &svgtree::Document,
+    state: &State,
+    passes: GroupPasses,
+    tree: &mut tree::Tree,
+) {
+    let last_def = tree.defs().last_child();
+
+    for node in nodes {
+        convert_element(node, state, &mut tree.root(), tree);
+    }
+
+    link_fe_image(svg_doc, state, &defs_after(tree, last_def.clone()), tree);
+
+    let defs = defs_after(tree, last_def);
+    if passes.remove_empty_groups {
+        remove_empty_groups_in(tree, &defs);
+    }
+
+    if passes.ungroup_groups {
+        ungroup_groups(state.opt, tree, &defs);
+    }
+}
+
+/// Returns `defs` elements after `last` or all of them when `last` is not set.
+fn defs_after(tree: &tree::Tree, last: Option<tree::Node>) -> Vec<tree::Node> {
+    match last {
+        Some(node) => node.following_siblings().skip(1).collect(),
+        None => tree.defs().children().collect(),
+    }
+}
+
+/// Converts a standalone shape element into a `Path`.
+///
+/// `element` should contain a single shape element, like `<rect width="10" height="10"/>`,
+/// without an SVG namespace. The element is processed just like inside a document,
+/// including the fill and stroke resolving, but inside a default 100x100 viewport.
+///
+/// Since there are no other elements, links to paint servers cannot be resolved
+/// and the paint fallback will be used. `transform` is stored in `Path::transform`.
+/// Markers are ignored.
+///
+/// Returns `None` when the element is not a shape or has an invalid geometry.
+pub fn element_to_path(element: &str, opt: &Options) -> Option<tree::Path> {
+    let text = format!(
+        "<svg xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink'>{}</svg>",
+        element,
+    );
+
+    let svg_doc = svgtree::Document::parse(&text, opt).ok()?;
+    let node = svg_doc.root_element().first_element_child()?;
+
+    let size = Size::new(100.0, 100.0).unwrap();
+    let state = State {
+        parent_clip_path: None,
+        parent_marker: None,
+        context_element: None,
+        fe_image_link: false,
+        size,
+        view_box: size.to_rect(0.0, 0.0),
+        #[cfg(feature = "text")]
+        db: opt.font_db.clone(),
+        opt,
+    };
+
+    let path = shapes::convert(node, &state)?;
+
+    let mut tree = tree::Tree::create(tree::Svg {
+        size,
+        view_box: tree::ViewBox {
+            rect: size.to_rect(0.0, 0.0),
+            aspect: tree::AspectRatio::default(),
+        },
+        metadata: None,
+    });
+
+    let mut root = tree.root();
+    convert_path(node, path, &state, &mut root, &mut tree);
+
+    let path = root.children().find_map(|child| match *child.borrow() {
+        tree::NodeKind::Path(ref path) => Some(path.clone()),
+        _ => None,
+    });
+
+    path.map(|path| tree::Path {
+        transform: node.attribute(AId::Transform).unwrap_or_default(),
+        ..path
+    })
+}
+
 fn resolve_svg_size(
     svg: &svgtree::Node,
     opt: &Options,
@@ -99,11 +289,12 @@ fn resolve_svg_size(
     let mut state = State {
         parent_clip_path: None,
         parent_marker: None,
+        context_element: None,
         fe_image_link: false,
         size: Size::new(100.0, 100.0).unwrap(),
         view_box: Rect::new(0.0, 0.0, 100.0, 100.0).unwrap(),
         #[cfg(feature = "text")]
-        db: Rc::new(RefCell::new(fontdb::Database::new())),
+        db: opt.font_db.clone(),
         opt,
     };
 
@@ -113,6 +304,27 @@ fn resolve_svg_size(
 
     let view_box = svg.get_viewbox();
 
+    // Percentages are relative to the container, when one is set.
+    if let Some(container) = opt.container_size {
+        if let Some(vbox) = view_box {
+            state.view_box = vbox;
+        }
+
+        let w = if width.unit == Unit::Percent {
+            container.width() * (width.num / 100.0)
+        } else {
+            svg.convert_user_length(AId::Width, &state, def)
+        };
+
+        let h = if height.unit == Unit::Percent {
+            container.height() * (height.num / 100.0)
+        } else {
+            svg.convert_user_length(AId::Height, &state, def)
+        };
+
+        return Size::new(w, h).ok_or(Error::InvalidSize);
+    }
+
     if (width.unit == Unit::Percent || height.unit == Unit::Percent) && view_box.is_none() {
         // TODO: it this case we should detect the bounding box of all elements,
         //       which is currently impossible
@@ -184,8 +396,16 @@ fn convert_element(
         return;
     }
 
-    let parent = &mut match convert_group(node, state, false, parent, tree) {
-        GroupKind::Create(g) => g,
+    // A basic shape clip path depends on the element's bounding box,
+    // so it can be resolved only after the element is converted.
+    let basic_shape_clip = clip_and_mask::has_basic_shape_clip(node);
+
+    let mut group = None;
+    let parent = &mut match convert_group(node, state, basic_shape_clip, parent, tree) {
+        GroupKind::Create(g) => {
+            group = Some(g.clone());
+            g
+        }
         GroupKind::Skip => parent.clone(),
         GroupKind::Ignore => return,
     };
@@ -222,6 +442,10 @@ fn convert_element(
         }
         _ => {}
     }
+
+    if let (true, Some(mut group)) = (basic_shape_clip, group) {
+        clip_and_mask::convert_basic_shape_clip(node, &mut group, tree);
+    }
 }
 
 // `clipPath` can have only shape and `text` children.
@@ -440,7 +664,13 @@ fn resolve_filter_stroke(
     Some(stroke.paint)
 }
 
-fn remove_empty_groups(tree: &mut tree::Tree) {
+pub(crate) fn remove_empty_groups(tree: &mut tree::Tree) {
+    let defs: Vec<_> = tree.defs().children().collect();
+    remove_empty_groups_in(tree, &defs);
+}
+
+/// Removes empty groups from the root elements and from the specified `defs` elements.
+fn remove_empty_groups_in(tree: &mut tree::Tree, defs: &[tree::Node]) {
     fn rm(parent: tree::Node) -> bool {
         let mut changed = false;
 
@@ -465,6 +695,8 @@ fn remove_empty_groups(tree: &mut tree::Tree) {
             if is_g && !node.has_children() {
                 node.detach();
                 changed = true;
+            } else if is_defs(&node) {
+                // Processed separately.
             } else {
                 if rm(node) {
                     changed = true;
@@ -476,11 +708,17 @@ fn remove_empty_groups(tree: &mut tree::Tree) {
     }
 
     while rm(tree.root()) {}
+
+    for node in defs {
+        while rm(node.clone()) {}
+    }
 }
 
+/// Ungroups groups in the root elements and in the specified `defs` elements.
 fn ungroup_groups(
     opt: &Options,
     tree: &mut tree::Tree,
+    defs: &[tree::Node],
 ) {
     fn ungroup(parent: tree::Node, opt: &Options) -> bool {
         let mut changed = false;
@@ -499,7 +737,7 @@ fn ungroup_groups(
                 && g.filter.is_none()
                 && g.enable_background.is_none()
                 && !(opt.keep_named_groups && !g.id.is_empty())
-                && !is_id_used(&parent.tree(), &g.id)
+                && (g.id.is_empty() || !is_id_used(&parent.tree(), &g.id))
             } else {
                 false
             };
@@ -529,6 +767,8 @@ fn ungroup_groups(
 
                 node.detach();
                 changed = true;
+            } else if is_defs(&node) {
+                // Processed separately.
             } else {
                 if ungroup(node, opt) {
                     changed = true;
@@ -540,6 +780,14 @@ fn ungroup_groups(
     }
 
     while ungroup(tree.root(), opt) {}
+
+    for node in defs {
+        while ungroup(node.clone(), opt) {}
+    }
+}
+
+fn is_defs(node: &tree::Node) -> bool {
+    matches!(*node.borrow(), tree::NodeKind::Defs)
 }
 
 fn remove_unused_defs(
@@ -561,19 +809,21 @@ fn remove_unused_defs(
     }
 }
 
+/// Converts elements referenced by `feImage` in the specified `defs` elements.
 fn link_fe_image(
     svg_doc: &svgtree::Document,
     state: &State,
+    defs: &[tree::Node],
     tree: &mut tree::Tree,
 ) {
     let mut ids = Vec::new();
     // TODO: simplify
-    for filter_node in tree.defs().children() {
+    for filter_node in defs {
         if let tree::NodeKind::Filter(ref filter) = *filter_node.borrow() {
             for fe in &filter.children {
                 if let tree::FilterKind::FeImage(ref fe_img) = fe.kind {
                     if let tree::FeImageKind::Use(ref id) = fe_img.data {
-                        if tree.defs_by_id(id).or(tree.node_by_id(id)).is_none() {
+                        if tree.node_by_id(id).is_none() {
                             // If `feImage` references a non-existing element,
                             // create it in `defs`.
                             if svg_doc.element_by_id(id).is_some() {
@@ -594,12 +844,14 @@ fn link_fe_image(
         if let Some(node) = svg_doc.element_by_id(&id) {
             let mut state = state.clone();
             state.fe_image_link = true;
+            let last_def = tree.defs().last_child();
             convert_element(node, &state, &mut tree.defs(), tree);
 
             // Check that node was actually created.
             // If not, reset to a dummy primitive.
-            if !tree.defs().descendants().any(|n| *n.id() == id) {
-                for mut filter_node in tree.defs().children() {
+            let new_defs = defs_after(tree, last_def);
+            if !new_defs.iter().any(|n| n.descendants().any(|n| *n.id() == id)) {
+                for mut filter_node in defs.iter().cloned() {
                     if let tree::NodeKind::Filter(ref mut filter) = *filter_node.borrow_mut() {
                         for fe in &mut filter.children {
                             fe.kind = filter::create_dummy_primitive();
@@ -697,40 +949,139 @@ fn convert_path(
 
     let has_bbox = path.has_bbox();
     let fill = style::resolve_fill(node, has_bbox, state, tree);
-    let stroke = style::resolve_stroke(node, has_bbox, state, tree);
-    let mut visibility = node.find_attribute(AId::Visibility).unwrap_or_default();
+    let mut stroke = style::resolve_stroke(node, has_bbox, state, tree);
+    if let Some(ref mut stroke) = stroke {
+        if node.attribute(AId::VectorEffect) == Some("non-scaling-stroke") {
+            stroke.non_scaling = is_non_scaling_stroke_allowed(parent, state, tree);
+        }
+    }
+
+    // `visibility` is inherited, but unlike `display` it can be reset by a child.
+    // So we have to resolve it for each path individually instead of skipping
+    // the whole subtree.
+    let visibility: tree::Visibility = node.find_attribute(AId::Visibility).unwrap_or_default();
     let rendering_mode = node
         .find_attribute(AId::ShapeRendering)
         .unwrap_or(state.opt.shape_rendering);
+    let paint_order = node.find_attribute(AId::PaintOrder).unwrap_or_default();
 
-    // If a path doesn't have a fill or a stroke than it's invisible.
-    // By setting `visibility` to `hidden` we are disabling the rendering of this path.
-    if fill.is_none() && stroke.is_none() {
-        visibility = tree::Visibility::Hidden;
-    }
-
+    // Markers are rendered even when a path itself has nothing to paint,
+    // so we have to check the actual `visibility` value here.
     let mut markers_group = None;
     if marker::is_valid(node) && visibility == tree::Visibility::Visible {
         let mut g = parent.append_kind(tree::NodeKind::Group(tree::Group::default()));
-        marker::convert(node, &path, state, &mut g, tree);
+        let mut marker_state = state.clone();
+        marker_state.context_element = Some((fill.clone(), stroke.clone()));
+        marker::convert(node, &path, &marker_state, &mut g, tree);
         markers_group = Some(g);
     }
 
-    parent.append_kind(tree::NodeKind::Path(tree::Path {
+    // If a path doesn't have a fill or a stroke than it's invisible.
+    // By setting `visibility` to `hidden` we are disabling the rendering of this path.
+    let visibility = if fill.is_none() && stroke.is_none() {
+        tree::Visibility::Hidden
+    } else {
+        visibility
+    };
+
+    let path = tree::Path {
         id: node.element_id().to_string(),
         transform: Default::default(),
         visibility,
         fill,
         stroke,
+        paint_order,
         rendering_mode,
         data: path,
-    }));
+    };
+
+    let mut g = match markers_group {
+        Some(g) => g,
+        None => {
+            parent.append_kind(tree::NodeKind::Path(path));
+            return;
+        }
+    };
+
+    match markers_paint_position(node) {
+        0 => {
+            // Markers group is already before `path`.
+            parent.append_kind(tree::NodeKind::Path(path));
+        }
+        1 => {
+            // Split `path` into two paths with markers in between.
+            // Each of them has only one paint, so the paint order is no longer needed.
+            let path = tree::Path { paint_order: tree::PaintOrder::default(), ..path };
+            let (first, second) = match paint_order {
+                tree::PaintOrder::FillAndStroke => {
+                    (tree::Path { stroke: None, ..path.clone() }, tree::Path { fill: None, ..path })
+                }
+                tree::PaintOrder::StrokeAndFill => {
+                    (tree::Path { fill: None, ..path.clone() }, tree::Path { stroke: None, ..path })
+                }
+            };
+
+            if first.fill.is_some() || first.stroke.is_some() {
+                g.insert_before(tree::Node::new(tree::NodeKind::Path(first)));
+                parent.append_kind(tree::NodeKind::Path(tree::Path { id: String::new(), ..second }));
+            } else {
+                parent.append_kind(tree::NodeKind::Path(second));
+            }
+        }
+        _ => {
+            // Insert markers group after `path`.
+            parent.append_kind(tree::NodeKind::Path(path));
+            g.detach();
+            parent.append(g);
+        }
+    }
+}
+
+/// Returns the position of markers in the `paint-order`.
+///
+/// `0` means before the fill and the stroke, `1` - between them and `2` - after them.
+///
+/// Omitted values are painted after the specified ones in the default order,
+/// which is `fill stroke markers`.
+fn markers_paint_position(node: svgtree::Node) -> usize {
+    let value = match node.find_node_with_attribute(AId::PaintOrder) {
+        Some(n) => n.attribute::<&str>(AId::PaintOrder).unwrap_or("normal"),
+        None => return 2,
+    };
+
+    let mut order = Vec::with_capacity(3);
+    for name in value.split_whitespace() {
+        match name {
+            "fill" | "stroke" | "markers" => {
+                if !order.contains(&name) {
+                    order.push(name);
+                }
+            }
+            // `normal` and invalid values.
+            _ => return 2,
+        }
+    }
 
-    // Insert markers group after `path`.
-    if let Some(mut g) = markers_group {
-        g.detach();
-        parent.append(g);
+    for name in &["fill", "stroke", "markers"] {
+        if !order.contains(name) {
+            order.push(name);
+        }
     }
+
+    order.iter().position(|name| *name == "markers").unwrap_or(2)
+}
+
+/// Checks that `vector-effect="non-scaling-stroke"` can be applied.
+///
+/// Markers are not affected, since they are still positioned in the current user space.
+fn is_non_scaling_stroke_allowed(
+    parent: &tree::Node,
+    state: &State,
+    tree: &tree::Tree,
+) -> bool {
+    // Elements inside `clipPath`, `mask`, `pattern` and `marker` are rendered
+    // in the user space of a referencing element, which is unknown at this point.
+    !(state.parent_clip_path.is_some() || state.parent_marker.is_some() || tree.is_in_defs(parent))
 }
 
 
@@ -6,6 +6,7 @@ use std::f64;
 
 use crate::{svgtree, tree, tree::prelude::*};
 use super::prelude::*;
+use super::style;
 
 
 pub enum ServerOrColor {
@@ -17,6 +18,8 @@ pub enum ServerOrColor {
         color: tree::Color,
         opacity: tree::Opacity,
     },
+    /// A valid paint server that doesn't paint anything.
+    None,
 }
 
 pub fn convert(
@@ -47,9 +50,9 @@ fn convert_linear(
     state: &State,
     tree: &mut tree::Tree,
 ) -> Option<ServerOrColor> {
-    let stops = convert_stops(find_gradient_with_stops(node)?);
+    let stops = find_gradient_with_stops(node).map(convert_stops).unwrap_or_default();
     if stops.len() < 2 {
-        return stops_to_color(&stops);
+        return Some(stops_to_color(&stops));
     }
 
     let units = convert_units(node, AId::GradientUnits, tree::Units::ObjectBoundingBox);
@@ -84,9 +87,9 @@ fn convert_radial(
     state: &State,
     tree: &mut tree::Tree,
 ) -> Option<ServerOrColor> {
-    let stops = convert_stops(find_gradient_with_stops(node)?);
+    let stops = find_gradient_with_stops(node).map(convert_stops).unwrap_or_default();
     if stops.len() < 2 {
-        return stops_to_color(&stops);
+        return Some(stops_to_color(&stops));
     }
 
     let units = convert_units(node, AId::GradientUnits, tree::Units::ObjectBoundingBox);
@@ -167,8 +170,10 @@ fn convert_pattern(
         resolve_number(node, AId::Width, units, state, Length::zero()),
         resolve_number(node, AId::Height, units, state, Length::zero()),
     );
+    // A zero or missing size disables the pattern rendering,
+    // which is not the same as an invalid link, so the paint fallback is not used.
     let rect = try_opt_warn_or!(
-        rect, None,
+        rect, Some(ServerOrColor::None),
         "Pattern '{}' has an invalid size. Skipped.", node.element_id()
     );
 
@@ -264,24 +269,31 @@ fn convert_stops(grad: svgtree::Node) -> Vec<tree::Stop> {
                 _ => prev_offset.num,
             };
             let offset = f64_bound(0.0, offset, 1.0);
+            // An offset smaller than the previous one is set to the previous one.
+            // Equal offsets are preserved, because they produce a hard color boundary.
+            let offset = offset.max(prev_offset.num);
             prev_offset = Length::new_number(offset);
 
+            // `stop-color` is not inherited, so a missing or an invalid value
+            // fallbacks to the initial one, which is black.
+            // Only `currentColor` depends on the inherited `color`.
             let color = match stop.attribute(AId::StopColor) {
                 Some(&svgtree::AttributeValue::CurrentColor) => {
-                    stop.find_attribute(AId::Color).unwrap_or_else(tree::Color::black)
+                    stop.find_attribute(AId::Color).unwrap_or_else(tree::Color::black).into()
                 }
                 Some(&svgtree::AttributeValue::Color(c)) => {
                     c
                 }
                 _ => {
-                    svgtypes::Color::black()
+                    svgtypes::Color::black().into()
                 }
             };
 
+            let opacity: tree::Opacity = stop.attribute(AId::StopOpacity).unwrap_or_default();
             stops.push(tree::Stop {
                 offset: offset.into(),
-                color,
-                opacity: stop.attribute(AId::StopOpacity).unwrap_or_default(),
+                color: color.to_color(),
+                opacity: style::color_alpha(color) * opacity,
             });
         }
     }
@@ -518,15 +530,23 @@ fn prepare_focal(cx: f64, cy: f64, r: f64, fx: f64, fy: f64) -> (f64, f64) {
     (line.x2, line.y2)
 }
 
+/// Converts a gradient with less than two stops.
+///
+/// Such gradients are resolved before `spreadMethod`, since it doesn't affect them.
 fn stops_to_color(
     stops: &[tree::Stop],
-) -> Option<ServerOrColor> {
-    if stops.is_empty() {
-        None
-    } else {
-        Some(ServerOrColor::Color {
-            color: stops[0].color,
-            opacity: stops[0].opacity,
-        })
+) -> ServerOrColor {
+    // 'If no stops are defined, then painting shall occur as if 'none'
+    // were specified as the paint style.
+    // If one stop is defined, then paint with the solid color fill
+    // using the color defined for that gradient stop.'
+    match stops.first() {
+        Some(stop) => {
+            ServerOrColor::Color {
+                color: stop.color,
+                opacity: stop.opacity,
+            }
+        }
+        None => ServerOrColor::None,
     }
 }
@@ -205,7 +205,7 @@ fn convert_ellipse(
     Some(Rc::new(ellipse_to_path(cx, cy, rx, ry)))
 }
 
-fn ellipse_to_path(
+pub fn ellipse_to_path(
     cx: f64,
     cy: f64,
     rx: f64,
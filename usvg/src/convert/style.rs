@@ -53,7 +53,9 @@ pub fn resolve_stroke(
         return None;
     };
 
-    let width = node.resolve_valid_length(AId::StrokeWidth, state, 1.0)?;
+    let width = node.resolve_valid_length(
+        AId::StrokeWidth, state, state.opt.default_stroke_width,
+    )?;
 
     // Must be bigger than 1.
     let miterlimit = node.find_attribute(AId::StrokeMiterlimit).unwrap_or(4.0);
@@ -69,6 +71,7 @@ pub fn resolve_stroke(
         width: tree::StrokeWidth::new(width),
         linecap: node.find_attribute(AId::StrokeLinecap).unwrap_or_default(),
         linejoin: node.find_attribute(AId::StrokeLinejoin).unwrap_or_default(),
+        non_scaling: false,
     };
 
     Some(stroke)
@@ -88,7 +91,18 @@ fn convert_paint(
             Some(tree::Paint::Color(c))
         }
         svgtree::AttributeValue::Color(c) => {
-            Some(tree::Paint::Color(*c))
+            *opacity = color_alpha(*c);
+            Some(tree::Paint::Color(c.to_color()))
+        }
+        svgtree::AttributeValue::String(s) => {
+            // `context-fill` and `context-stroke` are resolved only inside markers.
+            // Otherwise, they are treated as `none`.
+            let (fill, stroke) = state.context_element.as_ref()?;
+            match s.as_str() {
+                "context-fill" => fill.as_ref().map(|f| f.paint.clone()),
+                "context-stroke" => stroke.as_ref().map(|s| s.paint.clone()),
+                _ => None,
+            }
         }
         svgtree::AttributeValue::Paint(func_iri, fallback) => {
             if let Some(link) = node.document().element_by_id(func_iri) {
@@ -110,13 +124,19 @@ fn convert_paint(
                             *opacity = so;
                             Some(tree::Paint::Color(color))
                         }
+                        Some(paint_server::ServerOrColor::None) => {
+                            None
+                        }
                         None => {
                             from_fallback(node, *fallback)
                         }
                     }
                 } else {
+                    // 'If the IRI reference is not valid (e.g., it points to an object
+                    // that doesn't exist or the object is not a valid paint server),
+                    // then the fallback value (if specified) is used'
                     warn!("'{}' cannot be used to {} a shape.", tag_name, aid);
-                    None
+                    from_fallback(node, *fallback)
                 }
             } else {
                 from_fallback(node, *fallback)
@@ -146,6 +166,13 @@ fn from_fallback(
     }
 }
 
+/// Returns the alpha of an `rgba()` color as an opacity.
+///
+/// It should be multiplied by the corresponding opacity attribute.
+pub fn color_alpha(color: tree::ColorA) -> tree::Opacity {
+    tree::Opacity::new(color.alpha as f64 / 255.0)
+}
+
 // Prepare the 'stroke-dasharray' according to:
 // https://www.w3.org/TR/SVG11/painting.html#StrokeDasharrayProperty
 fn conv_dasharray(
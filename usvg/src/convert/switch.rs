@@ -18,7 +18,7 @@ static FEATURES: &[&str] = &[
     "http://www.w3.org/TR/SVG11/feature#ConditionalProcessing",
     "http://www.w3.org/TR/SVG11/feature#Image",
     "http://www.w3.org/TR/SVG11/feature#Style",
-    // "http://www.w3.org/TR/SVG11/feature#ViewportAttribute", // `clip` and `overflow`, not yet
+    "http://www.w3.org/TR/SVG11/feature#ViewportAttribute",
     "http://www.w3.org/TR/SVG11/feature#Shape",
     "http://www.w3.org/TR/SVG11/feature#Text",
     "http://www.w3.org/TR/SVG11/feature#BasicText",
@@ -41,6 +41,10 @@ static FEATURES: &[&str] = &[
     // "http://www.w3.org/TR/SVG11/feature#BasicFont",
 ];
 
+// We do not support any language extensions, so any extension URI will fail the test.
+// Kept as a list to make it clear where new ones should be added.
+static EXTENSIONS: &[&str] = &[];
+
 
 pub fn convert(
     node: svgtree::Node,
@@ -68,8 +72,14 @@ pub fn is_condition_passed(
         return false;
     }
 
-    if node.has_attribute(AId::RequiredExtensions) {
-        return false;
+    // 'The value is a list of URI references which identify the required extensions,
+    // with the individual values separated by white space.
+    // If a null string or empty string value is given to attribute 'requiredExtensions',
+    // the attribute evaluates to "false".'
+    if let Some(extensions) = node.attribute::<&str>(AId::RequiredExtensions) {
+        if !is_list_supported(extensions, EXTENSIONS) {
+            return false;
+        }
     }
 
     // 'The value is a list of feature strings, with the individual values separated by white space.
@@ -78,10 +88,8 @@ pub fn is_condition_passed(
     // If all of the given features are supported, then the attribute evaluates to true;
     // otherwise, the current element and its children are skipped and thus will not be rendered.'
     if let Some(features) = node.attribute::<&str>(AId::RequiredFeatures) {
-        for feature in features.split(' ') {
-            if !FEATURES.contains(&feature) {
-                return false;
-            }
+        if !is_list_supported(features, FEATURES) {
+            return false;
         }
     }
 
@@ -92,6 +100,21 @@ pub fn is_condition_passed(
     true
 }
 
+/// Checks that a white space separated list is not empty
+/// and all its items are present in `supported`.
+fn is_list_supported(list: &str, supported: &[&str]) -> bool {
+    let mut is_empty = true;
+    for item in list.split_whitespace() {
+        if !supported.contains(&item) {
+            return false;
+        }
+
+        is_empty = false;
+    }
+
+    !is_empty
+}
+
 /// SVG spec 5.8.5
 fn is_valid_sys_lang(
     node: svgtree::Node,
@@ -53,6 +53,20 @@ impl_enum_from_str!(TextAnchor,
 );
 
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum LengthAdjust {
+    Spacing,
+    SpacingAndGlyphs,
+}
+
+impl_enum_default!(LengthAdjust, Spacing);
+
+impl_enum_from_str!(LengthAdjust,
+    "spacing"           => LengthAdjust::Spacing,
+    "spacingAndGlyphs"  => LengthAdjust::SpacingAndGlyphs
+);
+
+
 pub struct TextPath {
     /// A text offset in SVG coordinates.
     ///
@@ -77,6 +91,9 @@ pub struct TextChunk {
     pub x: Option<f64>,
     pub y: Option<f64>,
     pub anchor: TextAnchor,
+    /// A target chunk length set via `textLength`.
+    pub text_length: Option<f64>,
+    pub length_adjust: LengthAdjust,
     pub spans: Vec<TextSpan>,
     pub text_flow: TextFlow,
     pub text: String,
@@ -109,6 +126,9 @@ pub struct TextSpan {
     pub visibility: tree::Visibility,
     pub letter_spacing: f64,
     pub word_spacing: f64,
+    /// Indicates that the font kerning is enabled.
+    pub kerning: bool,
+    pub paint_order: tree::PaintOrder,
 }
 
 impl TextSpan {
@@ -221,6 +241,7 @@ fn collect_text_chunks_impl(
             }
         };
 
+        let kerning = resolve_kerning(parent, state);
         let span = TextSpan {
             start: 0,
             end: 0,
@@ -231,8 +252,11 @@ fn collect_text_chunks_impl(
             decoration: resolve_decoration(text_node, parent, state, tree),
             visibility: parent.find_attribute(AId::Visibility).unwrap_or_default(),
             baseline_shift: resolve_baseline_shift(parent, state),
-            letter_spacing: parent.resolve_length(AId::LetterSpacing, state, 0.0),
+            letter_spacing: parent.resolve_length(AId::LetterSpacing, state, 0.0)
+                            + kerning.unwrap_or(0.0),
             word_spacing: parent.resolve_length(AId::WordSpacing, state, 0.0),
+            kerning: kerning.is_none(),
+            paint_order: parent.find_attribute(AId::PaintOrder).unwrap_or_default(),
         };
 
         let mut is_new_span = true;
@@ -259,10 +283,13 @@ fn collect_text_chunks_impl(
                 span2.start = 0;
                 span2.end = char_len;
 
+                let (text_length, length_adjust) = resolve_text_length(parent, state);
                 iter_state.chunks.push(TextChunk {
                     x: pos_list[iter_state.chars_count].x,
                     y: pos_list[iter_state.chars_count].y,
                     anchor,
+                    text_length,
+                    length_adjust,
                     spans: vec![span2],
                     text_flow: iter_state.text_flow.clone(),
                     text: c.to_string(),
@@ -295,6 +322,29 @@ fn collect_text_chunks_impl(
     }
 }
 
+/// Resolves `textLength` and `lengthAdjust` for a chunk that starts inside `node`.
+///
+/// The closest `text` or `tspan` element with a valid `textLength` is used.
+fn resolve_text_length(
+    node: svgtree::Node,
+    state: &State,
+) -> (Option<f64>, LengthAdjust) {
+    for n in node.ancestors() {
+        if let Some(length) = n.try_convert_user_length(AId::TextLength, state) {
+            if length.is_valid_length() {
+                let adjust = n.attribute(AId::LengthAdjust).unwrap_or_default();
+                return (Some(length), adjust);
+            }
+        }
+
+        if n.has_tag_name(EId::Text) {
+            break;
+        }
+    }
+
+    (None, LengthAdjust::default())
+}
+
 fn resolve_text_flow(
     node: svgtree::Node,
     state: &State,
@@ -366,7 +416,7 @@ fn resolve_font(
 
     let name_list: Vec<_> = name_list.iter().map(|s| s.as_str()).collect();
 
-    let mut db = state.db.borrow_mut();
+    let mut db = state.db.lock();
     let id = try_opt_warn_or!(
         db.select_best_match(&name_list, properties), None,
         "No match for '{}' font-family.", font_family
@@ -617,6 +667,20 @@ fn conv_text_decoration2(tspan: svgtree::Node) -> TextDecorationTypes {
     }
 }
 
+/// Resolves the `kerning` property.
+///
+/// Returns `None` for `auto`, which means that the font kerning should be used.
+/// Otherwise, the font kerning is disabled and the returned length
+/// should be added to the inter-character spacing, just like `letter-spacing`.
+fn resolve_kerning(
+    node: svgtree::Node,
+    state: &State,
+) -> Option<f64> {
+    let n = node.find_node_with_attribute(AId::Kerning)?;
+    let len = n.attribute::<Length>(AId::Kerning)?;
+    Some(units::convert_length(len, n, AId::Kerning, tree::Units::UserSpaceOnUse, state))
+}
+
 fn resolve_baseline_shift(
     node: svgtree::Node,
     state: &State,
@@ -738,3 +802,42 @@ pub fn convert_writing_mode(text_node: TextNode) -> WritingMode {
         WritingMode::LeftToRight
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_in_nested_tspan() {
+        let doc = svgtree::Document::parse("
+        <svg xmlns='http://www.w3.org/2000/svg'>
+            <text x='10' y='20'>a<tspan x='100 110 120 130' dy='5'>b<tspan x='50'>c</tspan>d</tspan>e</text>
+        </svg>
+        ", &Options::default()).unwrap();
+
+        let opt = Options::default();
+        let state = State {
+            parent_clip_path: None,
+            parent_marker: None,
+            context_element: None,
+            fe_image_link: false,
+            size: Size::new(100.0, 100.0).unwrap(),
+            view_box: Rect::new(0.0, 0.0, 100.0, 100.0).unwrap(),
+            db: opt.font_db.clone(),
+            opt: &opt,
+        };
+
+        let text_node = doc.descendants().find(|n| n.has_tag_name(EId::Text)).unwrap();
+        let list = resolve_positions_list(TextNode::new(text_node), &state);
+
+        let x: Vec<_> = list.iter().map(|p| p.x).collect();
+        assert_eq!(x, vec![Some(10.0), Some(100.0), Some(50.0), Some(120.0), None]);
+
+        let y: Vec<_> = list.iter().map(|p| p.y).collect();
+        assert_eq!(y, vec![Some(20.0), None, None, None, None]);
+
+        let dy: Vec<_> = list.iter().map(|p| p.dy).collect();
+        assert_eq!(dy, vec![None, Some(5.0), None, None, None]);
+    }
+}
@@ -58,7 +58,7 @@ pub fn convert(
     parent: &mut tree::Node,
     tree: &mut tree::Tree,
 ) {
-    state.db.borrow_mut().populate();
+    state.db.lock().populate();
 
     let text_node = TextNode::new(node.clone());
     let mut new_paths = text_to_paths(text_node, state, parent, tree);
@@ -124,6 +124,7 @@ fn text_to_paths(
         shaper::apply_writing_mode(writing_mode, &mut clusters);
         shaper::apply_letter_spacing(&chunk, &mut clusters);
         shaper::apply_word_spacing(&chunk, &mut clusters);
+        shaper::apply_length_adjust(chunk, &mut clusters);
         let curr_pos = shaper::resolve_clusters_positions(
             chunk, char_offset, &pos_list, &rotate_list, writing_mode, &mut clusters
         );
@@ -246,6 +247,7 @@ fn convert_span(
         visibility: span.visibility,
         fill,
         stroke: span.stroke.take(),
+        paint_order: span.paint_order,
         rendering_mode: tree::ShapeRendering::default(),
         data: Rc::new(path_data),
     };
@@ -357,6 +359,7 @@ fn convert_decoration(
         visibility: span.visibility,
         fill: decoration.fill.take(),
         stroke: decoration.stroke.take(),
+        paint_order: span.paint_order,
         data: Rc::new(path),
         .. tree::Path::default()
     }
@@ -13,6 +13,7 @@ use crate::tree::CubicBezExt;
 use super::convert::{
     ByteIndex,
     CharacterPosition,
+    LengthAdjust,
     TextAnchor,
     TextChunk,
     TextFlow,
@@ -164,7 +165,7 @@ pub fn outline_chunk(
 ) -> Vec<OutlinedCluster> {
     let mut glyphs = Vec::new();
     for span in &chunk.spans {
-        let tmp_glyphs = shape_text(&chunk.text, span.font, state);
+        let tmp_glyphs = shape_text(&chunk.text, span.font, span.kerning, state);
 
         // Do nothing with the first run.
         if glyphs.is_empty() {
@@ -191,7 +192,7 @@ pub fn outline_chunk(
     let mut clusters = Vec::new();
     for (range, byte_idx) in GlyphClusters::new(&glyphs) {
         if let Some(span) = chunk.span_at(byte_idx) {
-            let db = state.db.borrow();
+            let db = state.db.lock();
             clusters.push(outline_cluster(&glyphs[range], &chunk.text, span.font_size, &db));
         }
     }
@@ -203,9 +204,10 @@ pub fn outline_chunk(
 fn shape_text(
     text: &str,
     font: fontdb::Font,
+    kerning: bool,
     state: &State,
 ) -> Vec<Glyph> {
-    let mut glyphs = shape_text_with_font(text, font, state).unwrap_or_default();
+    let mut glyphs = shape_text_with_font(text, font, kerning, state).unwrap_or_default();
 
     // Remember all fonts used for shaping.
     let mut used_fonts = vec![font.id];
@@ -227,7 +229,7 @@ fn shape_text(
             };
 
             // Shape again, using a new font.
-            let fallback_glyphs = shape_text_with_font(text, fallback_font, state)
+            let fallback_glyphs = shape_text_with_font(text, fallback_font, kerning, state)
                 .unwrap_or_default();
 
             let all_matched = fallback_glyphs.iter().all(|g| !g.is_missing());
@@ -274,12 +276,15 @@ fn shape_text(
 /// Converts a text into a list of glyph IDs.
 ///
 /// This function will do the BIDI reordering and text shaping.
+///
+/// When `kerning` is `false`, the font kerning will be disabled.
 fn shape_text_with_font(
     text: &str,
     font: fontdb::Font,
+    kerning: bool,
     state: &State,
 ) -> Option<Vec<Glyph>> {
-    let db = state.db.borrow();
+    let db = state.db.lock();
 
     // We can't simplify this code because of lifetimes.
     let item = db.font(font.id);
@@ -312,7 +317,12 @@ fn shape_text_with_font(
             .add_str(sub_text)
             .set_direction(hb_direction);
 
-        let output = harfbuzz::shape(&hb_font, buffer, &[]);
+        let mut features = Vec::new();
+        if !kerning {
+            features.push(harfbuzz::Feature::new(harfbuzz::Tag::new('k', 'e', 'r', 'n'), 0, ..));
+        }
+
+        let output = harfbuzz::shape(&hb_font, buffer, &features);
 
         let positions = output.get_glyph_positions();
         let infos = output.get_glyph_infos();
@@ -409,7 +419,7 @@ fn find_font_for_char(
 ) -> Option<fontdb::Font> {
     let base_font_id = exclude_fonts[0];
 
-    let db = state.db.borrow();
+    let db = state.db.lock();
 
     // Iterate over fonts and check if any of them support the specified char.
     for item in db.fonts() {
@@ -796,6 +806,46 @@ pub fn apply_word_spacing(
     }
 }
 
+/// Applies the `textLength` and `lengthAdjust` attributes to a text chunk clusters.
+///
+/// [In the SVG spec](https://www.w3.org/TR/SVG11/text.html#TextElementTextLengthAttribute).
+pub fn apply_length_adjust(
+    chunk: &TextChunk,
+    clusters: &mut [OutlinedCluster],
+) {
+    let text_length = try_opt!(chunk.text_length);
+    let length = clusters_length(clusters);
+    if !length.is_valid_length() {
+        return;
+    }
+
+    match chunk.length_adjust {
+        LengthAdjust::Spacing => {
+            // The extra space is distributed between clusters,
+            // so a single cluster cannot be adjusted.
+            if clusters.len() < 2 {
+                return;
+            }
+
+            let diff = (text_length - length) / (clusters.len() - 1) as f64;
+            let last_idx = clusters.len() - 1;
+            for cluster in &mut clusters[..last_idx] {
+                cluster.advance += diff;
+            }
+        }
+        LengthAdjust::SpacingAndGlyphs => {
+            let sx = text_length / length;
+            let mut ts = tree::Transform::default();
+            ts.scale(sx, 1.0);
+
+            for cluster in clusters {
+                cluster.advance *= sx;
+                cluster.path.transform(ts);
+            }
+        }
+    }
+}
+
 /// Checks that the selected character is a word separator.
 ///
 /// According to: https://www.w3.org/TR/css-text-3/#word-separator
@@ -14,17 +14,10 @@ pub fn convert_length(
     object_units: tree::Units,
     state: &State,
 ) -> f64 {
-    let dpi = state.opt.dpi;
     let n = length.num;
     match length.unit {
-        Unit::None | Unit::Px => n,
         Unit::Em => n * resolve_font_size(node, state),
         Unit::Ex => n * resolve_font_size(node, state) / 2.0,
-        Unit::In => n * dpi,
-        Unit::Cm => n * dpi / 2.54,
-        Unit::Mm => n * dpi / 25.4,
-        Unit::Pt => n * dpi / 72.0,
-        Unit::Pc => n * dpi / 6.0,
         Unit::Percent => {
             if object_units == tree::Units::ObjectBoundingBox {
                 length.num / 100.0
@@ -49,6 +42,23 @@ pub fn convert_length(
                 }
             }
         }
+        unit => convert_absolute(n, unit, resolve_dpi(aid, state.opt)),
+    }
+}
+
+/// Converts an absolute length into user units.
+///
+/// Unitless and `px` values are already in user units,
+/// while physical units are converted using the specified DPI.
+/// Relative units are returned as is.
+pub(crate) fn convert_absolute(n: f64, unit: Unit, dpi: f64) -> f64 {
+    match unit {
+        Unit::In => n * dpi,
+        Unit::Cm => n * dpi / 2.54,
+        Unit::Mm => n * dpi / 25.4,
+        Unit::Pt => n * dpi / 72.0,
+        Unit::Pc => n * dpi / 6.0,
+        Unit::None | Unit::Px | Unit::Em | Unit::Ex | Unit::Percent => n,
     }
 }
 
@@ -72,6 +82,24 @@ pub fn convert_list(
     }
 }
 
+/// Returns a DPI for the axis the `aid` attribute belongs to.
+///
+/// Lengths that are not bound to an axis, like `r` or `stroke-width`,
+/// are using a normalized diagonal DPI, just like percentage values.
+pub(crate) fn resolve_dpi(aid: AId, opt: &Options) -> f64 {
+    let dpi_x = opt.dpi_x.unwrap_or(opt.dpi);
+    let dpi_y = opt.dpi_y.unwrap_or(opt.dpi);
+
+    match aid {
+          AId::X | AId::X1 | AId::X2 | AId::Cx | AId::Dx | AId::Fx | AId::Rx
+        | AId::Width | AId::RefX | AId::MarkerWidth => dpi_x,
+          AId::Y | AId::Y1 | AId::Y2 | AId::Cy | AId::Dy | AId::Fy | AId::Ry
+        | AId::Height | AId::RefY | AId::MarkerHeight => dpi_y,
+        _ if dpi_x == dpi_y => dpi_x,
+        _ => ((dpi_x * dpi_x + dpi_y * dpi_y) / 2.0).sqrt(),
+    }
+}
+
 fn convert_percent(length: Length, base: f64) -> f64 {
     base * length.num / 100.0
 }
@@ -82,22 +110,16 @@ pub fn resolve_font_size(node: svgtree::Node, state: &State) -> f64 {
     let mut font_size = state.opt.font_size;
     for n in nodes.iter().rev().skip(1) { // skip Root
         if let Some(length) = n.attribute::<Length>(AId::FontSize) {
-            let dpi = state.opt.dpi;
             let n = length.num;
             font_size = match length.unit {
-                Unit::None | Unit::Px => n,
                 Unit::Em => n * font_size,
                 Unit::Ex => n * font_size / 2.0,
-                Unit::In => n * dpi,
-                Unit::Cm => n * dpi / 2.54,
-                Unit::Mm => n * dpi / 25.4,
-                Unit::Pt => n * dpi / 72.0,
-                Unit::Pc => n * dpi / 6.0,
                 Unit::Percent => {
                     // If `font-size` has percent units that it's value
                     // is relative to the parent node `font-size`.
                     length.num * font_size * 0.01
                 }
+                unit => convert_absolute(n, unit, resolve_dpi(AId::FontSize, state.opt)),
             }
         } else if let Some(name) = n.attribute(AId::FontSize) {
             font_size = convert_named_font_size(name, font_size);
@@ -3,9 +3,11 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use std::rc::Rc;
+use std::str::FromStr;
 
 use crate::{svgtree, tree, tree::prelude::*, utils};
 use super::prelude::*;
+use super::units;
 
 
 pub fn convert(
@@ -40,10 +42,14 @@ pub fn convert(
             new_ts.append(&ts);
         }
 
-        if let Some(clip_rect) = get_clip_rect(node, child, state) {
-            let mut g = clip_element(node, clip_rect, orig_ts, parent, tree);
-            convert_children(child, new_ts, state, &mut g, tree);
-            return;
+        match get_clip_rect(node, child, state) {
+            ClipRect::None => {}
+            ClipRect::Rect(clip_rect) => {
+                let mut g = clip_element(node, clip_rect, orig_ts, parent, tree);
+                convert_children(child, new_ts, state, &mut g, tree);
+                return;
+            }
+            ClipRect::Empty => return,
         }
     }
 
@@ -76,12 +82,16 @@ pub fn convert_svg(
         new_ts.append(&ts);
     }
 
-    if let Some(clip_rect) = get_clip_rect(node, node, state) {
-        let mut g = clip_element(node, clip_rect, orig_ts, parent, tree);
-        convert_children(node, new_ts, state, &mut g, tree);
-    } else {
-        orig_ts.append(&new_ts);
-        convert_children(node, orig_ts, state, parent, tree);
+    match get_clip_rect(node, node, state) {
+        ClipRect::None => {
+            orig_ts.append(&new_ts);
+            convert_children(node, orig_ts, state, parent, tree);
+        }
+        ClipRect::Rect(clip_rect) => {
+            let mut g = clip_element(node, clip_rect, orig_ts, parent, tree);
+            convert_children(node, new_ts, state, &mut g, tree);
+        }
+        ClipRect::Empty => {}
     }
 }
 
@@ -162,35 +172,125 @@ fn convert_children(
     }
 }
 
+enum ClipRect {
+    /// Clipping is not required.
+    None,
+    Rect(Rect),
+    /// Everything is clipped out.
+    Empty,
+}
+
 fn get_clip_rect(
     use_node: svgtree::Node,
     symbol_node: svgtree::Node,
     state: &State,
-) -> Option<Rect> {
+) -> ClipRect {
     // No need to clip elements with overflow:visible.
-    if matches!(symbol_node.attribute(AId::Overflow), Some("visible") | Some("auto")) {
-        return None;
+    //
+    // Unless `slice` is set, because in this case the content is scaled
+    // to cover the whole viewport and must be clipped anyway.
+    //
+    // The `clip` property is ignored in both cases, because it applies
+    // only when `overflow` is not `visible` (CSS 2.1, 11.1.2).
+    let aspect: tree::AspectRatio = symbol_node.attribute(AId::PreserveAspectRatio).unwrap_or_default();
+    let is_slice = aspect.slice && symbol_node.has_attribute(AId::ViewBox);
+    let is_visible = matches!(symbol_node.attribute(AId::Overflow), Some("visible") | Some("auto"));
+    if !is_slice && is_visible {
+        return ClipRect::None;
     }
 
     let (x, y, w, h) = {
         let x = use_node.convert_user_length(AId::X, state, Length::zero());
         let y = use_node.convert_user_length(AId::Y, state, Length::zero());
-        let w = use_node.convert_user_length(AId::Width, state, Length::new(100.0, Unit::Percent));
-        let h = use_node.convert_user_length(AId::Height, state, Length::new(100.0, Unit::Percent));
+        let (w, h) = viewport_size(use_node, state);
         (x, y, w, h)
     };
 
     if !w.is_valid_length() || !h.is_valid_length() {
-        return None;
+        return ClipRect::None;
+    }
+
+    // The `clip` property is resolved relative to the viewport origin.
+    let clip = if is_visible { None } else { convert_clip_property(symbol_node, state) };
+    if let Some((top, right, bottom, left)) = clip {
+        let right = right.unwrap_or(w);
+        let bottom = bottom.unwrap_or(h);
+        let left = left.unwrap_or(0.0);
+        let top = top.unwrap_or(0.0);
+
+        return match Rect::new(x + left, y + top, right - left, bottom - top) {
+            Some(r) => ClipRect::Rect(r),
+            None => ClipRect::Empty,
+        };
     }
 
     // TODO: add a test case
     // Clip rect is not needed when it has the same size as a whole image.
     if w.fuzzy_eq(&state.size.width()) && h.fuzzy_eq(&state.size.height()) {
+        return ClipRect::None;
+    }
+
+    match Rect::new(x, y, w, h) {
+        Some(r) => ClipRect::Rect(r),
+        None => ClipRect::None,
+    }
+}
+
+/// The `top`, `right`, `bottom` and `left` offsets of the `clip` property,
+/// where `None` indicates `auto`.
+type ClipOffsets = (Option<f64>, Option<f64>, Option<f64>, Option<f64>);
+
+/// Parses the `clip` property.
+///
+/// Returns `None` for `auto` and invalid values.
+fn convert_clip_property(
+    node: svgtree::Node,
+    state: &State,
+) -> Option<ClipOffsets> {
+    // clip = rect(<top>, <right>, <bottom>, <left>) | auto
+    //
+    // Offsets can be separated by commas or, in legacy content, by white space.
+    let value: &str = node.attribute(AId::Clip)?;
+    let value = value.trim();
+    if !value.starts_with("rect(") || !value.ends_with(')') {
+        return None;
+    }
+
+    let mut offsets = [None; 4];
+    let mut count = 0;
+    let list = &value[5..value.len() - 1];
+    for item in list.split(|c: char| c == ',' || c.is_whitespace()).filter(|s| !s.is_empty()) {
+        if count == 4 {
+            return None;
+        }
+
+        if item != "auto" {
+            let length = match Length::from_str(item) {
+                Ok(v) => v,
+                Err(_) => {
+                    warn!("Failed to parse clip value: '{}'.", value);
+                    return None;
+                }
+            };
+
+            // Percentages are not allowed.
+            if length.unit == Unit::Percent {
+                return None;
+            }
+
+            offsets[count] = Some(units::convert_length(
+                length, node, AId::Clip, tree::Units::UserSpaceOnUse, state,
+            ));
+        }
+
+        count += 1;
+    }
+
+    if count != 4 {
         return None;
     }
 
-    Rect::new(x, y, w, h)
+    Some((offsets[0], offsets[1], offsets[2], offsets[3]))
 }
 
 /// Creates a free id for `clipPath`.
@@ -210,20 +310,44 @@ pub fn gen_clip_path_id(
     id
 }
 
+/// Resolves `width` and `height` of a `use` or a nested `svg` element.
+///
+/// When an `svg` element is referenced by a `use` element, `use`'s `width` and `height`
+/// override the referenced element ones. On other elements they are ignored.
+fn viewport_size(
+    node: svgtree::Node,
+    state: &State,
+) -> (f64, f64) {
+    let use_node = if node.tag_name() == Some(EId::Svg) {
+        node.parent_element().filter(|n| n.tag_name() == Some(EId::Use))
+    } else {
+        None
+    };
+
+    let convert = |aid| {
+        let n = match use_node {
+            Some(use_node) if use_node.has_attribute(aid) => use_node,
+            _ => node,
+        };
+
+        n.convert_user_length(aid, state, Length::new(100.0, Unit::Percent))
+    };
+
+    (convert(AId::Width), convert(AId::Height))
+}
+
 fn viewbox_transform(
     node: svgtree::Node,
     linked: svgtree::Node,
     state: &State,
 ) -> Option<tree::Transform> {
     let size = {
-        let def = Length::new(100.0, Unit::Percent);
-        let w = node.convert_user_length(AId::Width, state, def);
-        let h = node.convert_user_length(AId::Height, state, def);
+        let (w, h) = viewport_size(node, state);
         Size::new(w, h)
     }?;
 
     let vb = linked.get_viewbox()?;
     let aspect = linked.attribute(AId::PreserveAspectRatio).unwrap_or_default();
 
-    Some(utils::view_box_to_transform(vb, aspect, size))
+    utils::checked_view_box_to_transform(vb, aspect, size)
 }
@@ -24,6 +24,7 @@ pub struct FontItem {
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub struct ID(u16); // 65k fonts if more than enough!
 
+#[derive(Default)]
 pub struct Database {
     fonts: Vec<FontItem>,
     #[allow(dead_code)]
@@ -31,19 +32,18 @@ pub struct Database {
 }
 
 impl Database {
-    pub fn new() -> Self {
-        Database {
-            fonts: Vec::new(),
-            has_generic_fonts: false,
-        }
-    }
-
     pub fn populate(&mut self) {
         if self.fonts.is_empty() {
             load_all_fonts(&mut self.fonts);
         }
     }
 
+    pub fn load_fonts_dir(&mut self, dir: &Path) {
+        if let Some(dir) = dir.to_str() {
+            load_fonts_from(dir, &mut self.fonts);
+        }
+    }
+
     #[inline(never)]
     #[cfg(target_os = "linux")]
     fn collect_generic_fonts(&mut self) {
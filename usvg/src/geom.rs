@@ -4,7 +4,7 @@
 
 use std::{f64, fmt};
 
-use svgtypes::FuzzyEq;
+use svgtypes::{FuzzyEq, FuzzyZero, Transform};
 
 use crate::IsValidLength;
 
@@ -135,6 +135,45 @@ impl Size {
     pub fn to_rect(&self, x: f64, y: f64) -> Rect {
         Rect::new(x, y, self.width, self.height).unwrap()
     }
+
+    /// Scales the current size to the specified width preserving the aspect ratio.
+    #[inline]
+    pub fn scale_to_width(&self, new_width: f64) -> Option<Self> {
+        Size::new(new_width, new_width * self.height / self.width)
+    }
+
+    /// Scales the current size to the specified height preserving the aspect ratio.
+    #[inline]
+    pub fn scale_to_height(&self, new_height: f64) -> Option<Self> {
+        Size::new(new_height * self.width / self.height, new_height)
+    }
+
+    /// Scales the current size to the specified one preserving the aspect ratio.
+    ///
+    /// When `slice` is `false`, the result fits inside `to`, like `meet`
+    /// in `preserveAspectRatio`. Otherwise, the result covers `to` entirely.
+    ///
+    /// One of the sides is always equal to the `to` one, so it can be safely rounded up.
+    pub fn fit_within(&self, to: Self, slice: bool) -> Self {
+        let s = self.fit_scale(to, slice);
+        let (width, height) = if s == to.width / self.width {
+            (to.width, self.height * s)
+        } else {
+            (self.width * s, to.height)
+        };
+
+        // Can't fail, because both sizes are valid.
+        Size::new(width, height).unwrap()
+    }
+
+    /// Returns the scale factor used by `fit_within`.
+    ///
+    /// Can be infinite or zero when sizes differ too much.
+    pub(crate) fn fit_scale(&self, to: Self, slice: bool) -> f64 {
+        let sx = to.width / self.width;
+        let sy = to.height / self.height;
+        if slice { sx.max(sy) } else { sx.min(sy) }
+    }
 }
 
 impl fmt::Debug for Size {
@@ -326,3 +365,124 @@ impl fmt::Display for Rect {
         write!(f, "{:?}", self)
     }
 }
+
+
+/// Additional `Transform` methods.
+pub trait TransformExt {
+    /// Maps a rect using the current transform.
+    ///
+    /// Returns a bounding box of the transformed rect.
+    /// So if the transform contains a rotation or a skew, the result
+    /// will be bigger than the original rect.
+    ///
+    /// Returns `None` when the transformed rect has a zero size.
+    fn map_rect(&self, rect: Rect) -> Option<Rect>;
+
+    /// Returns an inverted transform.
+    ///
+    /// Returns `None` when the transform is not invertible.
+    fn invert(&self) -> Option<Transform>;
+}
+
+impl TransformExt for Transform {
+    fn map_rect(&self, rect: Rect) -> Option<Rect> {
+        if self.is_default() {
+            return Some(rect);
+        }
+
+        let points = [
+            self.apply(rect.left(), rect.top()),
+            self.apply(rect.right(), rect.top()),
+            self.apply(rect.right(), rect.bottom()),
+            self.apply(rect.left(), rect.bottom()),
+        ];
+
+        let mut min_x = f64::MAX;
+        let mut min_y = f64::MAX;
+        let mut max_x = f64::MIN;
+        let mut max_y = f64::MIN;
+        for &(x, y) in &points {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+
+        Rect::new(min_x, min_y, max_x - min_x, max_y - min_y)
+    }
+
+    fn invert(&self) -> Option<Transform> {
+        let det = self.a * self.d - self.b * self.c;
+        if det.is_fuzzy_zero() || !det.is_finite() {
+            return None;
+        }
+
+        Some(Transform::new(
+            self.d / det,
+            -self.b / det,
+            -self.c / det,
+            self.a / det,
+            (self.c * self.f - self.d * self.e) / det,
+            (self.b * self.e - self.a * self.f) / det,
+        ))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_rect_1() {
+        let ts = Transform::new_translate(10.0, 20.0);
+        let r = ts.map_rect(Rect::new(1.0, 2.0, 3.0, 4.0).unwrap()).unwrap();
+        assert!(r.fuzzy_eq(&Rect::new(11.0, 22.0, 3.0, 4.0).unwrap()));
+    }
+
+    #[test]
+    fn map_rect_2() {
+        let mut ts = Transform::new_translate(10.0, 20.0);
+        ts.rotate(90.0);
+        ts.scale(2.0, 3.0);
+
+        let r = ts.map_rect(Rect::new(0.0, 0.0, 10.0, 10.0).unwrap()).unwrap();
+        assert!(r.fuzzy_eq(&Rect::new(-20.0, 20.0, 30.0, 20.0).unwrap()));
+    }
+
+    #[test]
+    fn map_rect_3() {
+        let ts = Transform::new_scale(0.0, 1.0);
+        assert!(ts.map_rect(Rect::new(0.0, 0.0, 10.0, 10.0).unwrap()).is_none());
+    }
+
+    #[test]
+    fn invert_1() {
+        let mut ts = Transform::new_translate(10.0, 20.0);
+        ts.rotate(30.0);
+        ts.scale(2.0, 3.0);
+
+        let (x, y) = ts.apply(5.0, 7.0);
+        let (x, y) = ts.invert().unwrap().apply(x, y);
+        assert!(x.fuzzy_eq(&5.0));
+        assert!(y.fuzzy_eq(&7.0));
+
+        assert!(Transform::new_scale(0.0, 1.0).invert().is_none());
+    }
+
+    #[test]
+    fn fit_within_1() {
+        let size = Size::new(160.0, 90.0).unwrap();
+        let to = Size::new(100.0, 100.0).unwrap();
+        assert!(size.fit_within(to, false).fuzzy_eq(&Size::new(100.0, 56.25).unwrap()));
+        assert!(size.fit_within(to, true).fuzzy_eq(&Size::new(1600.0 / 9.0, 100.0).unwrap()));
+    }
+
+    #[test]
+    fn scale_to_width_1() {
+        let size = Size::new(160.0, 90.0).unwrap();
+        assert!(size.scale_to_width(32.0).unwrap().fuzzy_eq(&Size::new(32.0, 18.0).unwrap()));
+        assert!(size.scale_to_height(18.0).unwrap().fuzzy_eq(&Size::new(32.0, 18.0).unwrap()));
+        assert!(size.scale_to_width(0.0).is_none());
+    }
+}
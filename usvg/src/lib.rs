@@ -63,6 +63,13 @@ Full spec can be found [here](https://github.com/RazrFalcon/resvg/blob/master/do
 #![warn(missing_debug_implementations)]
 #![warn(missing_copy_implementations)]
 
+/// Emits a warning via `emit_warning`.
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        $crate::emit_warning(format_args!($($arg)+))
+    };
+}
+
 /// Unwraps `Option` and invokes `return` on `None`.
 macro_rules! try_opt {
     ($task:expr) => {
@@ -99,7 +106,7 @@ macro_rules! try_opt_warn {
         match $task {
             Some(v) => v,
             None => {
-                log::warn!($msg);
+                warn!($msg);
                 return;
             }
         }
@@ -108,7 +115,7 @@ macro_rules! try_opt_warn {
         match $task {
             Some(v) => v,
             None => {
-                log::warn!($fmt, $($arg)*);
+                warn!($fmt, $($arg)*);
                 return;
             }
         }
@@ -121,7 +128,7 @@ macro_rules! try_opt_warn_or {
         match $task {
             Some(v) => v,
             None => {
-                log::warn!($msg);
+                warn!($msg);
                 return $ret;
             }
         }
@@ -130,7 +137,7 @@ macro_rules! try_opt_warn_or {
         match $task {
             Some(v) => v,
             None => {
-                log::warn!($fmt, $($arg)*);
+                warn!($fmt, $($arg)*);
                 return $ret;
             }
         }
@@ -174,9 +181,12 @@ pub mod utils;
 mod convert;
 mod error;
 mod geom;
+mod optimize;
 mod options;
 mod svgtree;
 mod tree;
+mod validation;
+mod warning;
 #[cfg(feature = "text")] mod fontdb;
 
 /// Shorthand names for modules.
@@ -187,10 +197,14 @@ mod short {
 pub use xmlwriter::Options as XmlOptions;
 pub use xmlwriter::Indent as XmlIndent;
 
+pub use crate::convert::element_to_path;
 pub use crate::error::*;
 pub use crate::geom::*;
+pub use crate::optimize::*;
 pub use crate::options::*;
 pub use crate::tree::*;
+pub use crate::validation::*;
+pub use crate::warning::*;
 
 
 /// Checks that type has a default value.
@@ -0,0 +1,254 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::hash_map::{Entry, HashMap};
+use std::rc::Rc;
+
+use crate::{convert, svgtree, tree, Error, Options, XmlIndent, XmlOptions};
+use crate::convert::GroupPasses;
+
+
+/// Optimization passes used by `optimize`.
+///
+/// Unused `defs` are always removed during the conversion.
+/// Named groups can be preserved via `Options::keep_named_groups`.
+#[derive(Clone, Copy, Debug)]
+pub struct OptimizeOptions {
+    /// Removes empty groups.
+    ///
+    /// Groups with a filter are preserved, since a filter can be rendered without an input.
+    ///
+    /// Default: true
+    pub remove_empty_groups: bool,
+
+    /// Removes groups that have only a transform, by applying it to their children.
+    ///
+    /// Default: true
+    pub ungroup_groups: bool,
+
+    /// Replaces identical gradients with a single one.
+    ///
+    /// Default: true
+    pub deduplicate_gradients: bool,
+
+    /// Removes paths that will not be rendered, like hidden paths
+    /// or paths without a fill and a stroke.
+    ///
+    /// Paths inside groups with a clip path, mask or filter are preserved,
+    /// since they can affect the group bounding box.
+    ///
+    /// Default: true
+    pub remove_invisible_paths: bool,
+
+    /// Rounds path coordinates to the specified number of decimal places.
+    ///
+    /// Coordinates are in user units, so a small precision can affect
+    /// heavily scaled paths.
+    ///
+    /// `None` disables rounding.
+    ///
+    /// Default: 3
+    pub precision: Option<u8>,
+}
+
+impl Default for OptimizeOptions {
+    fn default() -> Self {
+        OptimizeOptions {
+            remove_empty_groups: true,
+            ungroup_groups: true,
+            deduplicate_gradients: true,
+            remove_invisible_paths: true,
+            precision: Some(3),
+        }
+    }
+}
+
+
+/// Converts an SVG string into a minimal SVG string.
+///
+/// The document is converted into a `Tree`, optimized according to `optimize_opt`
+/// and written back without indentation.
+pub fn optimize(
+    text: &str,
+    opt: &Options,
+    optimize_opt: &OptimizeOptions,
+) -> Result<String, Error> {
+    let _guard = crate::set_warning_handler(opt.warning_handler.as_ref());
+    let doc = svgtree::Document::parse(text, opt).map_err(Error::ParsingFailed)?;
+    let passes = GroupPasses {
+        remove_empty_groups: optimize_opt.remove_empty_groups,
+        ungroup_groups: optimize_opt.ungroup_groups,
+    };
+    let mut tree = convert::convert_doc_with_passes(&doc, opt, passes)?;
+
+    if optimize_opt.deduplicate_gradients {
+        deduplicate_gradients(&tree);
+    }
+
+    if optimize_opt.remove_invisible_paths {
+        remove_invisible_paths(&mut tree, optimize_opt.remove_empty_groups);
+    }
+
+    if let Some(precision) = optimize_opt.precision {
+        round_path_data(&tree, precision);
+    }
+
+    let xml_opt = XmlOptions {
+        use_single_quote: false,
+        indent: XmlIndent::None,
+        attributes_indent: XmlIndent::None,
+    };
+
+    Ok(tree.to_string(xml_opt))
+}
+
+fn deduplicate_gradients(tree: &tree::Tree) {
+    #[derive(PartialEq, Eq, Hash)]
+    enum Gradient<'a> {
+        Linear(&'a tree::LinearGradient),
+        Radial(&'a tree::RadialGradient),
+    }
+
+    // Duplicate gradient IDs mapped to the original ones.
+    let mut ids: HashMap<String, String> = HashMap::new();
+    let mut duplicates = Vec::new();
+
+    {
+        let nodes: Vec<_> = tree.defs().children().collect();
+        let kinds: Vec<_> = nodes.iter().map(|node| node.borrow()).collect();
+
+        // Gradients are hashed structurally, so identical ones will have the same key.
+        let mut originals: HashMap<Gradient, &str> = HashMap::new();
+        for (node, kind) in nodes.iter().zip(&kinds) {
+            let (gradient, id) = match **kind {
+                tree::NodeKind::LinearGradient(ref lg) => (Gradient::Linear(lg), &lg.id),
+                tree::NodeKind::RadialGradient(ref rg) => (Gradient::Radial(rg), &rg.id),
+                _ => continue,
+            };
+
+            match originals.entry(gradient) {
+                Entry::Occupied(e) => {
+                    ids.insert(id.clone(), e.get().to_string());
+                    duplicates.push(node.clone());
+                }
+                Entry::Vacant(e) => {
+                    e.insert(id);
+                }
+            }
+        }
+    }
+
+    if ids.is_empty() {
+        return;
+    }
+
+    let fix_paint = |paint: &mut tree::Paint| {
+        if let tree::Paint::Link(ref mut link) = *paint {
+            if let Some(id) = ids.get(link) {
+                *link = id.clone();
+            }
+        }
+    };
+
+    for mut node in tree.root().descendants() {
+        match *node.borrow_mut() {
+            tree::NodeKind::Path(ref mut path) => {
+                if let Some(ref mut fill) = path.fill {
+                    fix_paint(&mut fill.paint);
+                }
+
+                if let Some(ref mut stroke) = path.stroke {
+                    fix_paint(&mut stroke.paint);
+                }
+            }
+            tree::NodeKind::Group(ref mut g) => {
+                if let Some(ref mut paint) = g.filter_fill {
+                    fix_paint(paint);
+                }
+
+                if let Some(ref mut paint) = g.filter_stroke {
+                    fix_paint(paint);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for mut node in duplicates {
+        node.detach();
+    }
+}
+
+fn remove_invisible_paths(tree: &mut tree::Tree, remove_empty_groups: bool) {
+    let mut nodes = Vec::new();
+    for node in tree.root().descendants() {
+        if tree.is_in_defs(&node) {
+            continue;
+        }
+
+        let is_invisible = match *node.borrow() {
+            tree::NodeKind::Path(ref path) => {
+                   path.visibility != tree::Visibility::Visible
+                || (path.fill.is_none() && path.stroke.is_none())
+            }
+            _ => false,
+        };
+
+        if is_invisible && !affects_group_bbox(&node) {
+            nodes.push(node);
+        }
+    }
+
+    for mut node in nodes {
+        node.detach();
+    }
+
+    if remove_empty_groups {
+        convert::remove_empty_groups(tree);
+    }
+}
+
+/// Checks that any of the `node` ancestors relies on its bounding box.
+fn affects_group_bbox(node: &tree::Node) -> bool {
+    node.ancestors().skip(1).any(|n| {
+        if let tree::NodeKind::Group(ref g) = *n.borrow() {
+            g.clip_path.is_some() || g.mask.is_some() || g.filter.is_some()
+        } else {
+            false
+        }
+    })
+}
+
+fn round_path_data(tree: &tree::Tree, precision: u8) {
+    let k = 10f64.powi(precision as i32);
+    let round = |n: &mut f64| *n = (*n * k).round() / k;
+
+    for mut node in tree.root().descendants() {
+        if let tree::NodeKind::Path(ref mut path) = *node.borrow_mut() {
+            let mut data = (*path.data).clone();
+            for seg in &mut data.0 {
+                match *seg {
+                      tree::PathSegment::MoveTo { ref mut x, ref mut y }
+                    | tree::PathSegment::LineTo { ref mut x, ref mut y } => {
+                        round(x);
+                        round(y);
+                    }
+                    tree::PathSegment::CurveTo {
+                        ref mut x1, ref mut y1, ref mut x2, ref mut y2, ref mut x, ref mut y,
+                    } => {
+                        round(x1);
+                        round(y1);
+                        round(x2);
+                        round(y2);
+                        round(x);
+                        round(y);
+                    }
+                    tree::PathSegment::ClosePath => {}
+                }
+            }
+
+            path.data = Rc::new(data);
+        }
+    }
+}
@@ -2,12 +2,17 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "text")]
+use std::sync::{Arc, Mutex, MutexGuard};
 
 use crate::{
     ImageRendering,
+    Size,
     ShapeRendering,
     TextRendering,
+    WarningHandler,
 };
 
 
@@ -24,12 +29,39 @@ pub struct Options {
     /// Impact units conversion.
     pub dpi: f64,
 
+    /// Target DPI along the X-axis.
+    ///
+    /// Used for horizontal lengths instead of `dpi` when set.
+    pub dpi_x: Option<f64>,
+
+    /// Target DPI along the Y-axis.
+    ///
+    /// Used for vertical lengths instead of `dpi` when set.
+    pub dpi_y: Option<f64>,
+
     /// A default font family.
+    ///
+    /// Used when `font-family` is not set or none of the listed families are available.
     pub font_family: String,
 
     /// A default font size.
     pub font_size: f64,
 
+    /// A default stroke width.
+    ///
+    /// Used when a stroke is set, but `stroke-width` is not.
+    /// The SVG default is 1.
+    pub default_stroke_width: f64,
+
+    /// A size of the container the document is embedded into.
+    ///
+    /// Percentage values of the root element `width` and `height` will be resolved
+    /// relative to it, like in a browser. The `viewBox` is then fitted into the resulting
+    /// size according to `preserveAspectRatio`.
+    ///
+    /// When not set, percentages are resolved relative to the `viewBox` size.
+    pub container_size: Option<Size>,
+
     /// A list of languages that will be used to resolve the `systemLanguage`
     /// conditional attribute.
     ///
@@ -44,6 +76,8 @@ pub struct Options {
     /// Specifies the default text rendering method.
     ///
     /// Will be used when an SVG element's `text-rendering` property is set to `auto`.
+    ///
+    /// `OptimizeSpeed` disables text anti-aliasing, independently from `shape_rendering`.
     pub text_rendering: TextRendering,
 
     /// Specifies the default image rendering method.
@@ -56,6 +90,42 @@ pub struct Options {
     /// If set to `true`, all non-empty groups with `id` attribute will not
     /// be removed.
     pub keep_named_groups: bool,
+
+    /// Keep referenced image files unchecked.
+    ///
+    /// If set to `true`, referenced image files will not be opened during conversion
+    /// and their format will be detected by the file extension only.
+    /// Missing or invalid images will be skipped during rendering instead.
+    ///
+    /// Useful when the tree is used for analysis only.
+    pub keep_image_data: bool,
+
+    /// Render `foreignObject` elements as light gray boxes.
+    ///
+    /// `foreignObject` content (like HTML) cannot be rendered, so such elements
+    /// are skipped by default. A placeholder is useful to see where the unrenderable
+    /// content would be placed.
+    pub foreign_object_placeholder: bool,
+
+    /// A time in seconds at which SMIL animations should be evaluated.
+    ///
+    /// The evaluated values are baked into the tree, so the result is a static image.
+    /// Only simple `animate`, `animateTransform` and `set` elements are supported.
+    ///
+    /// If set to `None`, all animations will be ignored.
+    pub animation_time: Option<f64>,
+
+    /// A warnings handler.
+    ///
+    /// If set, all warnings will be passed to it instead of the `log` crate.
+    pub warning_handler: Option<WarningHandler>,
+
+    /// A fonts database.
+    ///
+    /// System fonts are scanned on the first text conversion, which is slow.
+    /// Since the handle is shared between `Options` clones, reusing the same `Options`
+    /// for multiple conversions will scan fonts only once.
+    pub font_db: FontDatabase,
 }
 
 impl Default for Options {
@@ -63,14 +133,72 @@ impl Default for Options {
         Options {
             path: None,
             dpi: 96.0,
+            dpi_x: None,
+            dpi_y: None,
             // Default font is user-agent dependent so we can use whatever we like.
             font_family: "Times New Roman".to_owned(),
             font_size: 12.0,
+            default_stroke_width: 1.0,
+            container_size: None,
             languages: vec!["en".to_string()],
             shape_rendering: ShapeRendering::default(),
             text_rendering: TextRendering::default(),
             image_rendering: ImageRendering::default(),
             keep_named_groups: false,
+            keep_image_data: false,
+            foreign_object_placeholder: false,
+            animation_time: None,
+            warning_handler: None,
+            font_db: FontDatabase::new(),
+        }
+    }
+}
+
+
+/// A shared fonts database handle.
+///
+/// Cloning the handle will not copy the database.
+/// Does nothing when the `text` feature is disabled.
+#[derive(Clone, Default)]
+pub struct FontDatabase(
+    #[cfg(feature = "text")] Arc<Mutex<crate::fontdb::Database>>,
+);
+
+impl FontDatabase {
+    /// Creates a new, empty database.
+    ///
+    /// Fonts will be loaded on the first use.
+    pub fn new() -> Self {
+        FontDatabase::default()
+    }
+
+    /// Loads fonts from the specified directory and its subdirectories.
+    ///
+    /// System fonts are loaded only when the database is empty,
+    /// so this can be used to get a reproducible text rendering.
+    ///
+    /// Does nothing when the `text` feature is disabled.
+    pub fn load_fonts_dir<P: AsRef<Path>>(&self, dir: P) {
+        #[cfg(feature = "text")]
+        {
+            self.lock().load_fonts_dir(dir.as_ref());
+        }
+
+        #[cfg(not(feature = "text"))]
+        {
+            let _ = dir;
         }
     }
+
+    #[cfg(feature = "text")]
+    pub(crate) fn lock(&self) -> MutexGuard<'_, crate::fontdb::Database> {
+        // The database is always in a valid state, even after a panic.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl fmt::Debug for FontDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "FontDatabase(..)")
+    }
 }
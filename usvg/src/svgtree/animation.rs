@@ -0,0 +1,300 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A minimal SMIL animation support.
+//!
+//! We do not support animations as is. Instead, animations are evaluated
+//! at the specified time and the resulting values are baked into the tree.
+//!
+//! Only `animate`, `animateTransform` and `set` elements that are direct
+//! children of the target element are supported. Event-based timing,
+//! `by` animations and `keyTimes`/`keySplines` are ignored.
+
+use std::str::FromStr;
+
+use super::AId;
+use super::parse::SVG_NS;
+
+
+/// Evaluates all animations of the `node` at the specified `time`.
+///
+/// Returns a list of attributes with values that should override
+/// the current ones. Animations are returned in the document order,
+/// so the later one wins.
+pub fn resolve(node: roxmltree::Node, time: f64) -> Vec<(AId, String)> {
+    let mut list = Vec::new();
+    for child in node.children() {
+        if child.tag_name().namespace() != Some(SVG_NS) {
+            continue;
+        }
+
+        let kind = match child.tag_name().name() {
+            "animate" => Kind::Animate,
+            "animateTransform" => Kind::AnimateTransform,
+            "set" => Kind::Set,
+            _ => continue,
+        };
+
+        if let Some(v) = resolve_animation(node, child, kind, time) {
+            list.push(v);
+        }
+    }
+
+    list
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Animate,
+    AnimateTransform,
+    Set,
+}
+
+fn resolve_animation(
+    parent: roxmltree::Node,
+    node: roxmltree::Node,
+    kind: Kind,
+    time: f64,
+) -> Option<(AId, String)> {
+    // Animations of other elements are not supported.
+    if node.attributes().iter().any(|a| a.name() == "href") {
+        return None;
+    }
+
+    let aid = if kind == Kind::AnimateTransform {
+        AId::Transform
+    } else {
+        let name = node.attribute("attributeName")?;
+        match AId::from_str(name) {
+            Some(aid) => aid,
+            None => {
+                warn!("Animation of the '{}' attribute is not supported.", name);
+                return None;
+            }
+        }
+    };
+
+    let progress = resolve_progress(node, time)?;
+
+    let value = if kind == Kind::Set {
+        node.attribute("to")?.to_string()
+    } else {
+        let values = collect_values(parent, node, aid)?;
+        let discrete = node.attribute("calcMode") == Some("discrete");
+        if kind == Kind::AnimateTransform {
+            let name = node.attribute("type").unwrap_or("translate");
+            let value = interpolate_transform(name, &values, progress, discrete)?;
+
+            // An additive animation is applied on top of the base value.
+            match (node.attribute("additive"), parent.attribute("transform")) {
+                (Some("sum"), Some(base)) => format!("{} {}", base, value),
+                _ => value,
+            }
+        } else {
+            interpolate(&values, progress, discrete)
+        }
+    };
+
+    Some((aid, value))
+}
+
+/// Returns a progress in a 0..1 range of the current animation iteration.
+///
+/// Returns `None` when the animation is not active.
+fn resolve_progress(node: roxmltree::Node, time: f64) -> Option<f64> {
+    let begin = match node.attribute("begin") {
+        Some(value) => parse_clock_value(value)?,
+        None => 0.0,
+    };
+
+    let dur = node.attribute("dur").and_then(parse_clock_value);
+
+    // `set` does not need a duration.
+    let dur = match dur {
+        Some(dur) if dur > 0.0 => dur,
+        _ => return if time >= begin { Some(1.0) } else { None },
+    };
+
+    let repeat_count = match node.attribute("repeatCount") {
+        Some("indefinite") => f64::INFINITY,
+        Some(value) => f64::from_str(value).ok().filter(|n| *n > 0.0).unwrap_or(1.0),
+        None => 1.0,
+    };
+
+    let t = time - begin;
+    if t < 0.0 {
+        return None;
+    }
+
+    if t >= dur * repeat_count {
+        return if node.attribute("fill") == Some("freeze") {
+            // The last value of the last iteration, which can be partial.
+            let p = repeat_count.fract();
+            Some(if p == 0.0 { 1.0 } else { p })
+        } else {
+            None
+        };
+    }
+
+    Some((t % dur) / dur)
+}
+
+/// Parses a clock value, like `1.5s`, `200ms` or `00:01.5`.
+fn parse_clock_value(text: &str) -> Option<f64> {
+    let text = text.trim();
+
+    if text.contains(':') {
+        let mut value = 0.0;
+        for part in text.split(':') {
+            value = value * 60.0 + f64::from_str(part).ok()?;
+        }
+
+        return Some(value);
+    }
+
+    let (number, scale) = if let Some(n) = text.strip_suffix("ms") {
+        (n, 0.001)
+    } else if let Some(n) = text.strip_suffix("min") {
+        (n, 60.0)
+    } else if let Some(n) = text.strip_suffix('h') {
+        (n, 3600.0)
+    } else if let Some(n) = text.strip_suffix('s') {
+        (n, 1.0)
+    } else {
+        (text, 1.0)
+    };
+
+    f64::from_str(number).ok().map(|n| n * scale)
+}
+
+fn collect_values(
+    parent: roxmltree::Node,
+    node: roxmltree::Node,
+    aid: AId,
+) -> Option<Vec<String>> {
+    if let Some(values) = node.attribute("values") {
+        let list: Vec<_> = values.split(';')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        return if list.is_empty() { None } else { Some(list) };
+    }
+
+    let to = node.attribute("to")?.to_string();
+    // A `to` animation starts from the base value.
+    let from = match node.attribute("from") {
+        Some(value) => value.to_string(),
+        None => parent.attribute(aid.to_str())?.to_string(),
+    };
+
+    Some(vec![from, to])
+}
+
+/// Returns a pair of values and an interpolation factor between them.
+fn pick_values<T>(values: &[T], progress: f64, discrete: bool) -> (&T, &T, f64) {
+    let len = values.len();
+    if discrete || len == 1 {
+        let idx = ((progress * len as f64) as usize).min(len - 1);
+        return (&values[idx], &values[idx], 0.0);
+    }
+
+    let pos = progress * (len - 1) as f64;
+    let idx = (pos as usize).min(len - 2);
+    (&values[idx], &values[idx + 1], pos - idx as f64)
+}
+
+fn interpolate(values: &[String], progress: f64, discrete: bool) -> String {
+    let (from, to, t) = pick_values(values, progress, discrete);
+    if t == 0.0 {
+        return from.clone();
+    }
+
+    if let (Ok(a), Ok(b)) = (svgtypes::Length::from_str(from), svgtypes::Length::from_str(to)) {
+        if a.unit == b.unit {
+            return svgtypes::Length::new(lerp(a.num, b.num, t), a.unit).to_string();
+        }
+    }
+
+    if let (Ok(a), Ok(b)) = (svgtypes::Color::from_str(from), svgtypes::Color::from_str(to)) {
+        let c = |a: u8, b: u8| lerp(a as f64, b as f64, t).round() as u8;
+        return svgtypes::Color::new(c(a.red, b.red), c(a.green, b.green), c(a.blue, b.blue))
+            .to_string();
+    }
+
+    // Values that cannot be interpolated are switched in the middle.
+    if t < 0.5 { from.clone() } else { to.clone() }
+}
+
+fn interpolate_transform(
+    name: &str,
+    values: &[String],
+    progress: f64,
+    discrete: bool,
+) -> Option<String> {
+    let mut lists = Vec::with_capacity(values.len());
+    for value in values {
+        let list: Vec<f64> = value.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .map(f64::from_str)
+            .collect::<Result<_, _>>()
+            .ok()?;
+
+        if list.is_empty() {
+            return None;
+        }
+
+        lists.push(list);
+    }
+
+    let (from, to, t) = pick_values(&lists, progress, discrete);
+    let list: Vec<String> = from.iter().enumerate().map(|(i, a)| {
+        let b = to.get(i).cloned().unwrap_or(*a);
+        lerp(*a, b, t).to_string()
+    }).collect();
+    let list = list.join(" ");
+
+    match name {
+        "translate" | "scale" | "rotate" | "skewX" | "skewY" => {
+            Some(format!("{}({})", name, list))
+        }
+        _ => {
+            warn!("'{}' is not a valid animateTransform type.", name);
+            None
+        }
+    }
+}
+
+#[inline]
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_clock_value_1() {
+        assert_eq!(parse_clock_value("2"), Some(2.0));
+        assert_eq!(parse_clock_value("1.5s"), Some(1.5));
+        assert_eq!(parse_clock_value("500ms"), Some(0.5));
+        assert_eq!(parse_clock_value("1min"), Some(60.0));
+        assert_eq!(parse_clock_value("01:02.5"), Some(62.5));
+        assert_eq!(parse_clock_value("click"), None);
+    }
+
+    #[test]
+    fn interpolate_1() {
+        let values = vec!["0".to_string(), "10px".to_string()];
+        // Different units are switched in the middle.
+        assert_eq!(interpolate(&values, 0.25, false), "0");
+        assert_eq!(interpolate(&values, 0.75, false), "10px");
+
+        let values = vec!["0".to_string(), "10".to_string(), "30".to_string()];
+        assert_eq!(interpolate(&values, 0.75, false), "20");
+        assert_eq!(interpolate(&values, 0.75, true), "30");
+    }
+}
@@ -5,13 +5,13 @@
 use std::fmt;
 use std::collections::HashMap;
 
-use log::warn;
-
 use svgtypes::FuzzyEq;
 
 use crate::geom::Rect;
 use crate::tree;
 
+mod animation;
+
 mod parse;
 pub use parse::*;
 
@@ -25,6 +25,8 @@ pub struct Document {
     nodes: Vec<NodeData>,
     attrs: Vec<Attribute>,
     links: HashMap<String, NodeId>,
+    metadata: Option<String>,
+    recursive_links: Vec<(NodeId, AId)>,
 }
 
 impl Document {
@@ -52,6 +54,19 @@ impl Document {
     pub fn get(&self, id: NodeId) -> Node {
         Node { id, d: &self.nodes[id.0], doc: self }
     }
+
+    /// Returns elements and attributes with recursive links
+    /// that were reset to `none` during parsing.
+    #[inline]
+    pub fn recursive_links(&self) -> &[(NodeId, AId)] {
+        &self.recursive_links
+    }
+
+    /// Returns the raw XML of the root `metadata` element.
+    #[inline]
+    pub fn metadata(&self) -> Option<&str> {
+        self.metadata.as_deref()
+    }
 }
 
 impl fmt::Debug for Document {
@@ -142,7 +157,7 @@ pub enum AttributeValue {
     CurrentColor,
     Angle(svgtypes::Angle),
     AspectRatio(svgtypes::AspectRatio),
-    Color(svgtypes::Color),
+    Color(tree::ColorA),
     EnableBackground(tree::EnableBackground),
     Length(svgtypes::Length),
     Link(String),
@@ -608,7 +623,7 @@ macro_rules! impl_from_value {
     )
 }
 
-impl_from_value!(svgtypes::Color, Color);
+impl_from_value!(tree::ColorA, Color);
 impl_from_value!(svgtypes::Length, Length);
 impl_from_value!(svgtypes::ViewBox, ViewBox);
 impl_from_value!(svgtypes::AspectRatio, AspectRatio);
@@ -617,6 +632,13 @@ impl_from_value!(f64, Number);
 impl_from_value!(tree::Opacity, Opacity);
 impl_from_value!(tree::EnableBackground, EnableBackground);
 
+impl FromValue<'_> for svgtypes::Color {
+    fn get(node: Node, aid: AId) -> Option<Self> {
+        // The alpha is ignored.
+        tree::ColorA::get(node, aid).map(|c| c.to_color())
+    }
+}
+
 impl<'a> FromValue<'a> for &'a AttributeValue {
     fn get(node: Node<'a>, aid: AId) -> Option<Self> {
         node.attributes().iter().find(|a| a.name == aid).map(|a| &a.value)
@@ -738,6 +760,7 @@ impl AId {
     pub fn is_presentation(&self) -> bool {
         matches!(self,
               AId::BaselineShift
+            | AId::Clip
             | AId::ClipPath
             | AId::ClipRule
             | AId::Color
@@ -757,13 +780,16 @@ impl AId {
             | AId::FontVariant
             | AId::FontWeight
             | AId::ImageRendering
+            | AId::Kerning
             | AId::LetterSpacing
             | AId::MarkerEnd
             | AId::MarkerMid
             | AId::MarkerStart
             | AId::Mask
+            | AId::MaskType
             | AId::Opacity
             | AId::Overflow
+            | AId::PaintOrder
             | AId::ShapeRendering
             | AId::StopColor
             | AId::StopOpacity
@@ -778,6 +804,7 @@ impl AId {
             | AId::TextAnchor
             | AId::TextDecoration
             | AId::TextRendering
+            | AId::VectorEffect
             | AId::Visibility
             | AId::WordSpacing
             | AId::WritingMode)
@@ -794,6 +821,7 @@ impl AId {
     pub fn allows_inherit_value(&self) -> bool {
         matches!(self,
               AId::BaselineShift
+            | AId::Clip
             | AId::ClipPath
             | AId::ClipRule
             | AId::Color
@@ -813,13 +841,16 @@ impl AId {
             | AId::FontVariant
             | AId::FontWeight
             | AId::ImageRendering
+            | AId::Kerning
             | AId::LetterSpacing
             | AId::MarkerEnd
             | AId::MarkerMid
             | AId::MarkerStart
             | AId::Mask
+            | AId::MaskType
             | AId::Opacity
             | AId::Overflow
+            | AId::PaintOrder
             | AId::ShapeRendering
             | AId::StopColor
             | AId::StopOpacity
@@ -834,6 +865,7 @@ impl AId {
             | AId::TextAnchor
             | AId::TextDecoration
             | AId::TextRendering
+            | AId::VectorEffect
             | AId::Visibility
             | AId::WordSpacing
             | AId::WritingMode)
@@ -843,15 +875,18 @@ impl AId {
 fn is_non_inheritable(id: AId) -> bool {
     matches!(id,
           AId::BaselineShift
+        | AId::Clip
         | AId::ClipPath
         | AId::Display
         | AId::Filter
         | AId::FloodColor
         | AId::FloodOpacity
         | AId::Mask
+        | AId::MaskType
         | AId::Opacity
         | AId::Overflow
         | AId::StopColor
         | AId::StopOpacity
-        | AId::TextDecoration)
+        | AId::TextDecoration
+        | AId::VectorEffect)
 }
@@ -18,6 +18,7 @@ pub enum EId {
     FeDiffuseLighting,
     FeDisplacementMap,
     FeDistantLight,
+    FeDropShadow,
     FeFlood,
     FeFuncA,
     FeFuncB,
@@ -35,6 +36,7 @@ pub enum EId {
     FeTile,
     FeTurbulence,
     Filter,
+    ForeignObject,
     G,
     Image,
     Line,
@@ -60,73 +62,75 @@ pub enum EId {
 }
 
 static ELEMENTS: Map<EId> = Map {
-    key: 3558916427560184125,
+    key: 8726423004985182586,
     disps: &[
-        (1, 13),
-        (5, 3),
-        (0, 19),
-        (6, 24),
+        (3, 16),
+        (0, 39),
+        (1, 45),
+        (0, 37),
+        (6, 16),
         (0, 0),
-        (0, 3),
+        (2, 36),
         (0, 1),
-        (11, 10),
-        (0, 21),
-        (0, 0),
-        (5, 0),
+        (0, 15),
+        (19, 46),
+        (16, 2),
     ],
     entries: &[
-        ("feConvolveMatrix", EId::FeConvolveMatrix),
-        ("tspan", EId::Tspan),
+        ("feOffset", EId::FeOffset),
+        ("defs", EId::Defs),
+        ("feDropShadow", EId::FeDropShadow),
         ("style", EId::Style),
-        ("feFuncB", EId::FeFuncB),
-        ("rect", EId::Rect),
-        ("marker", EId::Marker),
-        ("feDiffuseLighting", EId::FeDiffuseLighting),
-        ("g", EId::G),
-        ("symbol", EId::Symbol),
-        ("pattern", EId::Pattern),
+        ("circle", EId::Circle),
         ("path", EId::Path),
-        ("feFuncR", EId::FeFuncR),
+        ("polyline", EId::Polyline),
         ("a", EId::A),
         ("textPath", EId::TextPath),
-        ("use", EId::Use),
-        ("feFuncA", EId::FeFuncA),
-        ("tref", EId::Tref),
-        ("circle", EId::Circle),
-        ("fePointLight", EId::FePointLight),
-        ("defs", EId::Defs),
-        ("feTile", EId::FeTile),
+        ("ellipse", EId::Ellipse),
+        ("feDiffuseLighting", EId::FeDiffuseLighting),
         ("image", EId::Image),
         ("stop", EId::Stop),
-        ("feGaussianBlur", EId::FeGaussianBlur),
-        ("feFlood", EId::FeFlood),
-        ("polyline", EId::Polyline),
-        ("feComponentTransfer", EId::FeComponentTransfer),
-        ("linearGradient", EId::LinearGradient),
+        ("rect", EId::Rect),
         ("feFuncG", EId::FeFuncG),
-        ("ellipse", EId::Ellipse),
-        ("clipPath", EId::ClipPath),
-        ("feMerge", EId::FeMerge),
         ("feSpotLight", EId::FeSpotLight),
-        ("feBlend", EId::FeBlend),
-        ("svg", EId::Svg),
-        ("feColorMatrix", EId::FeColorMatrix),
         ("feTurbulence", EId::FeTurbulence),
+        ("feDisplacementMap", EId::FeDisplacementMap),
+        ("feFuncR", EId::FeFuncR),
+        ("feBlend", EId::FeBlend),
         ("feSpecularLighting", EId::FeSpecularLighting),
-        ("switch", EId::Switch),
-        ("feMorphology", EId::FeMorphology),
-        ("feImage", EId::FeImage),
-        ("feMergeNode", EId::FeMergeNode),
-        ("feOffset", EId::FeOffset),
-        ("polygon", EId::Polygon),
+        ("linearGradient", EId::LinearGradient),
+        ("feFuncA", EId::FeFuncA),
+        ("fePointLight", EId::FePointLight),
+        ("marker", EId::Marker),
         ("feComposite", EId::FeComposite),
+        ("feTile", EId::FeTile),
+        ("feConvolveMatrix", EId::FeConvolveMatrix),
+        ("g", EId::G),
         ("radialGradient", EId::RadialGradient),
-        ("line", EId::Line),
-        ("feDisplacementMap", EId::FeDisplacementMap),
         ("feDistantLight", EId::FeDistantLight),
+        ("feColorMatrix", EId::FeColorMatrix),
+        ("switch", EId::Switch),
+        ("symbol", EId::Symbol),
+        ("feComponentTransfer", EId::FeComponentTransfer),
+        ("polygon", EId::Polygon),
+        ("pattern", EId::Pattern),
+        ("tref", EId::Tref),
+        ("line", EId::Line),
+        ("filter", EId::Filter),
+        ("tspan", EId::Tspan),
+        ("feGaussianBlur", EId::FeGaussianBlur),
+        ("feImage", EId::FeImage),
+        ("use", EId::Use),
+        ("clipPath", EId::ClipPath),
+        ("feMergeNode", EId::FeMergeNode),
+        ("feFuncB", EId::FeFuncB),
+        ("feMerge", EId::FeMerge),
+        ("feFlood", EId::FeFlood),
+        ("foreignObject", EId::ForeignObject),
         ("mask", EId::Mask),
+        ("svg", EId::Svg),
+        ("feMorphology", EId::FeMorphology),
         ("text", EId::Text),
-        ("filter", EId::Filter),
     ],
 };
 
@@ -161,6 +165,7 @@ pub enum AId {
     BaselineShift,
     Bias,
     Class,
+    Clip,
     ClipPath,
     ClipRule,
     ClipPathUnits,
@@ -209,6 +214,8 @@ pub enum AId {
     K4,
     KernelMatrix,
     KernelUnitLength,
+    Kerning,
+    LengthAdjust,
     LetterSpacing,
     LightingColor,
     LimitingConeAngle,
@@ -219,6 +226,7 @@ pub enum AId {
     MarkerUnits,
     MarkerWidth,
     Mask,
+    MaskType,
     MaskContentUnits,
     MaskUnits,
     Mode,
@@ -229,6 +237,7 @@ pub enum AId {
     Order,
     Orient,
     Overflow,
+    PaintOrder,
     PatternContentUnits,
     PatternTransform,
     PatternUnits,
@@ -279,9 +288,11 @@ pub enum AId {
     TextAnchor,
     TextDecoration,
     TextRendering,
+    TextLength,
     Transform,
     Type,
     Values,
+    VectorEffect,
     ViewBox,
     Visibility,
     Width,
@@ -301,178 +312,186 @@ pub enum AId {
 static ATTRIBUTES: Map<AId> = Map {
     key: 3213172566270843353,
     disps: &[
-        (0, 32),
-        (0, 1),
-        (0, 16),
-        (0, 31),
-        (9, 125),
-        (1, 4),
-        (5, 102),
-        (0, 44),
-        (11, 69),
-        (5, 77),
-        (0, 20),
-        (0, 8),
-        (0, 3),
-        (0, 8),
-        (2, 43),
-        (1, 0),
         (0, 0),
-        (25, 133),
-        (12, 99),
+        (0, 130),
+        (1, 75),
+        (4, 23),
+        (0, 0),
+        (0, 0),
+        (4, 92),
         (0, 3),
-        (0, 68),
-        (0, 33),
-        (1, 54),
-        (0, 64),
-        (2, 119),
-        (0, 18),
         (0, 7),
-        (1, 19),
-        (9, 46),
+        (0, 121),
+        (0, 28),
+        (3, 103),
+        (2, 124),
+        (5, 94),
+        (3, 44),
+        (0, 27),
+        (0, 6),
+        (0, 103),
+        (3, 109),
+        (5, 129),
+        (106, 101),
+        (14, 89),
+        (0, 12),
+        (2, 141),
+        (0, 26),
+        (0, 5),
+        (4, 32),
+        (1, 97),
+        (0, 0),
+        (4, 87),
     ],
     entries: &[
-        ("pointsAtZ", AId::PointsAtZ),
-        ("stop-color", AId::StopColor),
-        ("xChannelSelector", AId::XChannelSelector),
-        ("systemLanguage", AId::SystemLanguage),
-        ("cy", AId::Cy),
-        ("dy", AId::Dy),
-        ("preserveAlpha", AId::PreserveAlpha),
-        ("preserveAspectRatio", AId::PreserveAspectRatio),
-        ("lighting-color", AId::LightingColor),
-        ("stroke-dashoffset", AId::StrokeDashoffset),
-        ("word-spacing", AId::WordSpacing),
-        ("font-style", AId::FontStyle),
-        ("edgeMode", AId::EdgeMode),
-        ("pointsAtY", AId::PointsAtY),
-        ("id", AId::Id),
-        ("type", AId::Type),
-        ("targetX", AId::TargetX),
-        ("fill", AId::Fill),
-        ("k3", AId::K3),
-        ("kernelUnitLength", AId::KernelUnitLength),
-        ("viewBox", AId::ViewBox),
-        ("baseFrequency", AId::BaseFrequency),
-        ("stroke", AId::Stroke),
-        ("divisor", AId::Divisor),
-        ("slope", AId::Slope),
-        ("markerUnits", AId::MarkerUnits),
         ("d", AId::D),
-        ("clipPathUnits", AId::ClipPathUnits),
-        ("stop-opacity", AId::StopOpacity),
-        ("transform", AId::Transform),
-        ("stroke-dasharray", AId::StrokeDasharray),
+        ("font-size", AId::FontSize),
+        ("r", AId::R),
         ("dx", AId::Dx),
-        ("specularExponent", AId::SpecularExponent),
-        ("z", AId::Z),
-        ("direction", AId::Direction),
-        ("letter-spacing", AId::LetterSpacing),
-        ("stroke-miterlimit", AId::StrokeMiterlimit),
-        ("overflow", AId::Overflow),
-        ("order", AId::Order),
-        ("intercept", AId::Intercept),
-        ("operator", AId::Operator),
-        ("font-variant", AId::FontVariant),
+        ("x", AId::X),
         ("filterUnits", AId::FilterUnits),
-        ("values", AId::Values),
-        ("filter", AId::Filter),
-        ("rotate", AId::Rotate),
-        ("href", AId::Href),
-        ("requiredExtensions", AId::RequiredExtensions),
+        ("radius", AId::Radius),
+        ("primitiveUnits", AId::PrimitiveUnits),
+        ("kerning", AId::Kerning),
+        ("diffuseConstant", AId::DiffuseConstant),
+        ("marker-end", AId::MarkerEnd),
+        ("stroke-opacity", AId::StrokeOpacity),
+        ("limitingConeAngle", AId::LimitingConeAngle),
+        ("type", AId::Type),
+        ("rx", AId::Rx),
+        ("kernelMatrix", AId::KernelMatrix),
+        ("pointsAtZ", AId::PointsAtZ),
+        ("operator", AId::Operator),
+        ("stroke", AId::Stroke),
+        ("shape-rendering", AId::ShapeRendering),
+        ("ry", AId::Ry),
+        ("spreadMethod", AId::SpreadMethod),
+        ("targetX", AId::TargetX),
+        ("stroke-dasharray", AId::StrokeDasharray),
+        ("clipPathUnits", AId::ClipPathUnits),
+        ("mask-type", AId::MaskType),
         ("font-stretch", AId::FontStretch),
+        ("font-weight", AId::FontWeight),
+        ("style", AId::Style),
+        ("refY", AId::RefY),
+        ("refX", AId::RefX),
+        ("fill", AId::Fill),
+        ("gradientTransform", AId::GradientTransform),
+        ("space", AId::Space),
+        ("opacity", AId::Opacity),
+        ("stitchTiles", AId::StitchTiles),
+        ("id", AId::Id),
+        ("color", AId::Color),
+        ("cx", AId::Cx),
         ("clip-path", AId::ClipPath),
+        ("href", AId::Href),
+        ("marker-start", AId::MarkerStart),
+        ("gradientUnits", AId::GradientUnits),
+        ("flood-color", AId::FloodColor),
+        ("markerHeight", AId::MarkerHeight),
+        ("intercept", AId::Intercept),
+        ("text-anchor", AId::TextAnchor),
+        ("stroke-dashoffset", AId::StrokeDashoffset),
+        ("transform", AId::Transform),
+        ("class", AId::Class),
+        ("overflow", AId::Overflow),
+        ("azimuth", AId::Azimuth),
+        ("k3", AId::K3),
+        ("yChannelSelector", AId::YChannelSelector),
+        ("height", AId::Height),
+        ("baseline-shift", AId::BaselineShift),
+        ("x2", AId::X2),
         ("x1", AId::X1),
+        ("maskContentUnits", AId::MaskContentUnits),
+        ("pointsAtY", AId::PointsAtY),
         ("pointsAtX", AId::PointsAtX),
+        ("color-interpolation-filters", AId::ColorInterpolationFilters),
+        ("stdDeviation", AId::StdDeviation),
+        ("specularConstant", AId::SpecularConstant),
+        ("clip", AId::Clip),
         ("k4", AId::K4),
-        ("font-weight", AId::FontWeight),
-        ("class", AId::Class),
+        ("surfaceScale", AId::SurfaceScale),
+        ("visibility", AId::Visibility),
+        ("specularExponent", AId::SpecularExponent),
+        ("paint-order", AId::PaintOrder),
+        ("vector-effect", AId::VectorEffect),
+        ("systemLanguage", AId::SystemLanguage),
+        ("in2", AId::In2),
+        ("orient", AId::Orient),
+        ("text-rendering", AId::TextRendering),
+        ("font-style", AId::FontStyle),
+        ("stroke-linejoin", AId::StrokeLinejoin),
+        ("image-rendering", AId::ImageRendering),
+        ("enable-background", AId::EnableBackground),
+        ("slope", AId::Slope),
+        ("letter-spacing", AId::LetterSpacing),
+        ("result", AId::Result),
         ("patternTransform", AId::PatternTransform),
-        ("markerHeight", AId::MarkerHeight),
-        ("writing-mode", AId::WritingMode),
+        ("values", AId::Values),
+        ("numOctaves", AId::NumOctaves),
+        ("baseFrequency", AId::BaseFrequency),
+        ("fill-opacity", AId::FillOpacity),
+        ("text-decoration", AId::TextDecoration),
         ("clip-rule", AId::ClipRule),
-        ("y1", AId::Y1),
-        ("patternContentUnits", AId::PatternContentUnits),
-        ("primitiveUnits", AId::PrimitiveUnits),
-        ("x2", AId::X2),
-        ("y", AId::Y),
-        ("marker-mid", AId::MarkerMid),
+        ("xChannelSelector", AId::XChannelSelector),
+        ("filter", AId::Filter),
+        ("divisor", AId::Divisor),
+        ("preserveAlpha", AId::PreserveAlpha),
+        ("lengthAdjust", AId::LengthAdjust),
+        ("stop-opacity", AId::StopOpacity),
+        ("targetY", AId::TargetY),
         ("offset", AId::Offset),
-        ("stroke-width", AId::StrokeWidth),
-        ("markerWidth", AId::MarkerWidth),
-        ("fill-opacity", AId::FillOpacity),
-        ("refX", AId::RefX),
-        ("maskContentUnits", AId::MaskContentUnits),
-        ("shape-rendering", AId::ShapeRendering),
-        ("amplitude", AId::Amplitude),
-        ("stitchTiles", AId::StitchTiles),
+        ("exponent", AId::Exponent),
+        ("font-variant", AId::FontVariant),
+        ("requiredExtensions", AId::RequiredExtensions),
+        ("display", AId::Display),
         ("maskUnits", AId::MaskUnits),
+        ("amplitude", AId::Amplitude),
+        ("kernelUnitLength", AId::KernelUnitLength),
+        ("direction", AId::Direction),
+        ("order", AId::Order),
+        ("stroke-width", AId::StrokeWidth),
+        ("stroke-miterlimit", AId::StrokeMiterlimit),
+        ("y1", AId::Y1),
+        ("patternContentUnits", AId::PatternContentUnits),
         ("fx", AId::Fx),
-        ("color", AId::Color),
-        ("numOctaves", AId::NumOctaves),
-        ("stdDeviation", AId::StdDeviation),
-        ("y2", AId::Y2),
+        ("viewBox", AId::ViewBox),
         ("mask", AId::Mask),
-        ("spreadMethod", AId::SpreadMethod),
-        ("display", AId::Display),
-        ("opacity", AId::Opacity),
-        ("font-size", AId::FontSize),
-        ("stroke-linejoin", AId::StrokeLinejoin),
-        ("image-rendering", AId::ImageRendering),
-        ("azimuth", AId::Azimuth),
-        ("in", AId::In),
-        ("ry", AId::Ry),
-        ("bias", AId::Bias),
-        ("radius", AId::Radius),
-        ("enable-background", AId::EnableBackground),
-        ("gradientUnits", AId::GradientUnits),
-        ("specularConstant", AId::SpecularConstant),
-        ("limitingConeAngle", AId::LimitingConeAngle),
-        ("flood-color", AId::FloodColor),
-        ("scale", AId::Scale),
-        ("x", AId::X),
-        ("diffuseConstant", AId::DiffuseConstant),
-        ("refY", AId::RefY),
-        ("yChannelSelector", AId::YChannelSelector),
-        ("style", AId::Style),
-        ("gradientTransform", AId::GradientTransform),
+        ("font-family", AId::FontFamily),
+        ("marker-mid", AId::MarkerMid),
+        ("lighting-color", AId::LightingColor),
         ("fill-rule", AId::FillRule),
-        ("height", AId::Height),
-        ("kernelMatrix", AId::KernelMatrix),
-        ("text-anchor", AId::TextAnchor),
-        ("stroke-linecap", AId::StrokeLinecap),
-        ("text-decoration", AId::TextDecoration),
-        ("rx", AId::Rx),
-        ("requiredFeatures", AId::RequiredFeatures),
-        ("patternUnits", AId::PatternUnits),
-        ("mode", AId::Mode),
-        ("marker-start", AId::MarkerStart),
-        ("visibility", AId::Visibility),
-        ("result", AId::Result),
+        ("word-spacing", AId::WordSpacing),
+        ("startOffset", AId::StartOffset),
+        ("y2", AId::Y2),
+        ("scale", AId::Scale),
+        ("stop-color", AId::StopColor),
+        ("cy", AId::Cy),
+        ("preserveAspectRatio", AId::PreserveAspectRatio),
+        ("markerWidth", AId::MarkerWidth),
         ("seed", AId::Seed),
-        ("in2", AId::In2),
-        ("cx", AId::Cx),
-        ("k2", AId::K2),
+        ("writing-mode", AId::WritingMode),
+        ("tableValues", AId::TableValues),
+        ("dy", AId::Dy),
         ("flood-opacity", AId::FloodOpacity),
+        ("mode", AId::Mode),
         ("k1", AId::K1),
-        ("text-rendering", AId::TextRendering),
-        ("r", AId::R),
-        ("marker-end", AId::MarkerEnd),
-        ("surfaceScale", AId::SurfaceScale),
-        ("tableValues", AId::TableValues),
-        ("orient", AId::Orient),
-        ("color-interpolation-filters", AId::ColorInterpolationFilters),
+        ("edgeMode", AId::EdgeMode),
+        ("k2", AId::K2),
+        ("y", AId::Y),
+        ("rotate", AId::Rotate),
+        ("bias", AId::Bias),
+        ("textLength", AId::TextLength),
         ("points", AId::Points),
-        ("baseline-shift", AId::BaselineShift),
+        ("requiredFeatures", AId::RequiredFeatures),
+        ("markerUnits", AId::MarkerUnits),
         ("elevation", AId::Elevation),
+        ("in", AId::In),
+        ("z", AId::Z),
+        ("patternUnits", AId::PatternUnits),
         ("width", AId::Width),
-        ("font-family", AId::FontFamily),
-        ("space", AId::Space),
-        ("startOffset", AId::StartOffset),
+        ("stroke-linecap", AId::StrokeLinecap),
         ("fy", AId::Fy),
-        ("stroke-opacity", AId::StrokeOpacity),
-        ("targetY", AId::TargetY),
-        ("exponent", AId::Exponent),
     ],
 };
 
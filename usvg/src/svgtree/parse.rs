@@ -2,26 +2,27 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::borrow::Cow;
 use std::rc::Rc;
 use std::str::FromStr;
 use std::collections::HashMap;
 
-use log::warn;
-
 pub use roxmltree::Error;
 
-use crate::tree;
+use crate::{tree, Options};
 use crate::Rect;
+use crate::convert::units;
+use super::animation;
 use super::{Document, Attribute, AId, EId, Node, NodeId, NodeKind, NodeData, AttributeValue};
 
-const SVG_NS: &str = "http://www.w3.org/2000/svg";
+pub(crate) const SVG_NS: &str = "http://www.w3.org/2000/svg";
 const XLINK_NS: &str = "http://www.w3.org/1999/xlink";
 const XML_NAMESPACE_NS: &str = "http://www.w3.org/XML/1998/namespace";
 
 
 impl Document {
-    pub fn parse(text: &str) -> Result<Document, Error> {
-        parse(text)
+    pub fn parse(text: &str, opt: &Options) -> Result<Document, Error> {
+        parse(text, opt)
     }
 
     fn append(&mut self, parent_id: NodeId, kind: NodeKind) -> NodeId {
@@ -52,30 +53,39 @@ impl Document {
         new_child_id
     }
 
-    fn append_attribute(&mut self, tag_name: EId, aid: AId, value: &str) {
+    /// Parses and appends an attribute.
+    ///
+    /// Returns `false` when the value is invalid and the attribute was skipped.
+    fn append_attribute(&mut self, tag_name: EId, aid: AId, value: &str) -> bool {
         let value2 = parse_svg_attribute(tag_name, aid, value);
         if let Ok(value) = value2 {
             self.attrs.push(Attribute {
                 name: aid,
                 value,
             });
+
+            true
         } else {
             // Invalid `enable-background` is not an error
             // since we are ignoring the `accumulate` value.
             if aid != AId::EnableBackground {
                 warn!("Failed to parse {} value: '{}'.", aid, value);
             }
+
+            false
         }
     }
 }
 
-fn parse(text: &str) -> Result<Document, Error> {
+fn parse(text: &str, opt: &Options) -> Result<Document, Error> {
     let xml = roxmltree::Document::parse(text)?;
 
     let mut doc = Document {
         nodes: Vec::new(),
         attrs: Vec::new(),
         links: HashMap::new(),
+        metadata: None,
+        recursive_links: Vec::new(),
     };
 
     // Add a root node.
@@ -89,7 +99,7 @@ fn parse(text: &str) -> Result<Document, Error> {
 
     let style_sheet = resolve_css(&xml);
 
-    parse_xml_node_children(xml.root(), xml.root(), doc.root().id, &style_sheet, false, &mut doc);
+    parse_xml_node_children(xml.root(), xml.root(), doc.root().id, &style_sheet, false, opt, &mut doc);
 
     // Check that the root element is `svg`.
     match doc.root().first_element_child() {
@@ -110,6 +120,8 @@ fn parse(text: &str) -> Result<Document, Error> {
     }
     doc.links = links;
 
+    doc.metadata = parse_metadata(text, &xml);
+
     fix_recursive_patterns(&mut doc);
     fix_recursive_links(EId::ClipPath, AId::ClipPath, &mut doc);
     fix_recursive_links(EId::Mask, AId::Mask, &mut doc);
@@ -118,6 +130,42 @@ fn parse(text: &str) -> Result<Document, Error> {
     Ok(doc)
 }
 
+/// Returns the raw XML of the first `metadata` child of the root element.
+///
+/// The metadata content is not processed and is stored as is,
+/// so it can be written back during the export.
+fn parse_metadata(text: &str, xml: &roxmltree::Document) -> Option<String> {
+    let svg = xml.root_element();
+    let node = svg.children().find(|n| {
+        n.is_element() &&
+        n.tag_name().namespace() == Some(SVG_NS) &&
+        n.tag_name().name() == "metadata"
+    })?;
+
+    let mut raw = text[node.range()].to_string();
+
+    // Metadata usually relies on namespaces declared on the root element,
+    // like `rdf` or `dc`, so we have to copy them to keep the XML valid.
+    let name_end = raw.find(|c: char| c.is_whitespace() || c == '/' || c == '>')?;
+    let start_tag_end = raw.find('>')?;
+    let mut declarations = String::new();
+    for ns in svg.namespaces() {
+        let prefix = match ns.name() {
+            Some("xml") | None => continue,
+            Some(prefix) => prefix,
+        };
+
+        let redeclared = raw[..start_tag_end].contains(&format!("xmlns:{}=", prefix));
+        if !redeclared && raw.contains(&format!("{}:", prefix)) {
+            declarations.push_str(&format!(" xmlns:{}=\"{}\"", prefix, ns.uri()));
+        }
+    }
+
+    raw.insert_str(name_end, &declarations);
+
+    Some(raw)
+}
+
 fn parse_tag_name(node: roxmltree::Node) -> Option<EId> {
     if !node.is_element() {
         return None;
@@ -136,10 +184,11 @@ fn parse_xml_node_children(
     parent_id: NodeId,
     style_sheet: &simplecss::StyleSheet,
     ignore_ids: bool,
+    opt: &Options,
     doc: &mut Document,
 ) {
     for node in parent.children() {
-        parse_xml_node(node, origin, parent_id, style_sheet, ignore_ids, doc);
+        parse_xml_node(node, origin, parent_id, style_sheet, ignore_ids, opt, doc);
     }
 }
 
@@ -149,6 +198,7 @@ fn parse_xml_node(
     parent_id: NodeId,
     style_sheet: &simplecss::StyleSheet,
     ignore_ids: bool,
+    opt: &Options,
     doc: &mut Document,
 ) {
     let mut tag_name = match parse_tag_name(node) {
@@ -160,18 +210,28 @@ fn parse_xml_node(
         return;
     }
 
+    // `foreignObject` content cannot be rendered,
+    // so it's either skipped or replaced with a placeholder rectangle.
+    if tag_name == EId::ForeignObject {
+        if opt.foreign_object_placeholder {
+            parse_svg_element(node, parent_id, EId::Rect, style_sheet, ignore_ids, opt, doc);
+        }
+
+        return;
+    }
+
     // Treat links as groups.
     if tag_name == EId::A {
         tag_name = EId::G;
     }
 
-    let node_id = parse_svg_element(node, parent_id, tag_name, style_sheet, ignore_ids, doc);
+    let node_id = parse_svg_element(node, parent_id, tag_name, style_sheet, ignore_ids, opt, doc);
     if tag_name == EId::Text {
-        parse_svg_text_element(node, node_id, style_sheet, doc);
+        parse_svg_text_element(node, node_id, style_sheet, opt, doc);
     } else if tag_name == EId::Use {
-        parse_svg_use_element(node, origin, node_id, style_sheet, doc);
+        parse_svg_use_element(node, origin, node_id, style_sheet, opt, doc);
     } else {
-        parse_xml_node_children(node, origin, node_id, style_sheet, ignore_ids, doc);
+        parse_xml_node_children(node, origin, node_id, style_sheet, ignore_ids, opt, doc);
     }
 }
 
@@ -181,6 +241,7 @@ fn parse_svg_element(
     tag_name: EId,
     style_sheet: &simplecss::StyleSheet,
     ignore_ids: bool,
+    opt: &Options,
     doc: &mut Document,
 ) -> NodeId {
     let attrs_start_idx = doc.attrs.len();
@@ -203,10 +264,13 @@ fn parse_svg_element(
             continue;
         }
 
-        append_attribute(parent_id, tag_name, aid, attr.value(), doc);
+        let value = resolve_rem_units(tag_name, aid, attr.value(), doc, opt);
+        append_attribute(parent_id, tag_name, aid, &value, doc);
     }
 
     let mut insert_attribute = |aid, value: &str| {
+        let value = &*resolve_rem_units(tag_name, aid, value, doc, opt);
+
         // Check that attribute already exists.
         let idx = doc.attrs[attrs_start_idx..].iter_mut().position(|a| a.name == aid);
 
@@ -225,40 +289,62 @@ fn parse_svg_element(
         }
     };
 
-    // Apply CSS.
-    for rule in &style_sheet.rules {
-        if rule.selector.matches(&XmlNode(xml_node)) {
-            for declaration in &rule.declarations {
-                // TODO: preform XML attribute normalization
-                if let Some(aid) = AId::from_str(declaration.name) {
-                    // Parse only the presentation attributes.
-                    // `transform` isn't a presentation attribute, but should be parsed anyway.
-                    if aid.is_presentation() || aid == AId::Transform {
-                        insert_attribute(aid, declaration.value);
+    let mut insert_declaration = |declaration: &simplecss::Declaration| {
+        // TODO: preform XML attribute normalization
+        if let Some(aid) = AId::from_str(declaration.name) {
+            // Parse only the presentation attributes.
+            // `transform` isn't a presentation attribute, but should be parsed anyway.
+            if aid.is_presentation() || aid == AId::Transform {
+                insert_attribute(aid, declaration.value);
+            }
+        } else if declaration.name == "marker" {
+            insert_attribute(AId::MarkerStart, declaration.value);
+            insert_attribute(AId::MarkerMid, declaration.value);
+            insert_attribute(AId::MarkerEnd, declaration.value);
+        }
+    };
+
+    // The cascade order is: style sheet, `style` attribute,
+    // `!important` style sheet declarations and `!important` `style` attribute declarations.
+    // Style sheet rules are already sorted by specificity.
+    let style: Vec<_> = match xml_node.attribute("style") {
+        Some(value) => simplecss::DeclarationTokenizer::from(value).collect(),
+        None => Vec::new(),
+    };
+
+    for important in &[false, true] {
+        // Apply CSS.
+        for rule in &style_sheet.rules {
+            if rule.selector.matches(&XmlNode(xml_node)) {
+                for declaration in &rule.declarations {
+                    if declaration.important == *important {
+                        insert_declaration(declaration);
                     }
-                } else if declaration.name == "marker" {
-                    insert_attribute(AId::MarkerStart, declaration.value);
-                    insert_attribute(AId::MarkerMid, declaration.value);
-                    insert_attribute(AId::MarkerEnd, declaration.value);
                 }
             }
         }
-    }
 
-    // Split a `style` attribute.
-    if let Some(value) = xml_node.attribute("style") {
-        for declaration in simplecss::DeclarationTokenizer::from(value) {
-            // TODO: preform XML attribute normalization
-            if let Some(aid) = AId::from_str(declaration.name) {
-                // Parse only the presentation attributes.
-                // `transform` isn't a presentation attribute, but should be parsed anyway.
-                if aid.is_presentation() || aid == AId::Transform {
-                    insert_attribute(aid, declaration.value);
-                }
+        // Split a `style` attribute.
+        for declaration in &style {
+            if declaration.important == *important {
+                insert_declaration(declaration);
             }
         }
     }
 
+    // Animations override everything else.
+    if let Some(time) = opt.animation_time {
+        for (aid, value) in animation::resolve(xml_node, time) {
+            insert_attribute(aid, &value);
+        }
+    }
+
+    // A `foreignObject` placeholder ignores the element's own paint.
+    if parse_tag_name(xml_node) == Some(EId::ForeignObject) {
+        insert_attribute(AId::Fill, "lightgray");
+        insert_attribute(AId::Stroke, "none");
+    }
+
     let node_id = doc.append(parent_id, NodeKind::Element {
         tag_name,
         attributes: attrs_start_idx..doc.attrs.len(),
@@ -292,8 +378,7 @@ fn append_attribute(
         return resolve_inherit(parent_id, tag_name, aid, doc);
     }
 
-    doc.append_attribute(tag_name, aid, value);
-    true
+    doc.append_attribute(tag_name, aid, value)
 }
 
 fn parse_svg_attribute(
@@ -301,6 +386,8 @@ fn parse_svg_attribute(
     aid: AId,
     value: &str,
 ) -> Result<AttributeValue, svgtypes::Error> {
+    let value = &*normalize_func_iri(value);
+
     Ok(match aid {
         AId::Href => {
             // `href` can contain base64 data and we do store it as is.
@@ -338,7 +425,8 @@ fn parse_svg_attribute(
         | AId::RefX | AId::RefY
         | AId::Width | AId::Height
         | AId::MarkerWidth | AId::MarkerHeight
-        | AId::StartOffset => {
+        | AId::StartOffset
+        | AId::TextLength => {
             AttributeValue::Length(svgtypes::Length::from_str(value)?)
         }
 
@@ -408,32 +496,29 @@ fn parse_svg_attribute(
             }
         }
 
+        AId::Fill | AId::Stroke if value == "context-fill" || value == "context-stroke" => {
+            // Not supported by `svgtypes`.
+            AttributeValue::String(value.to_string())
+        }
+
         AId::Fill => {
-            match svgtypes::Paint::from_str(value) {
-                Ok(svgtypes::Paint::None) => AttributeValue::None,
-                Ok(svgtypes::Paint::Inherit) => unreachable!(),
-                Ok(svgtypes::Paint::CurrentColor) => AttributeValue::CurrentColor,
-                Ok(svgtypes::Paint::Color(color)) => AttributeValue::Color(color),
-                Ok(svgtypes::Paint::FuncIRI(link, fallback)) => {
-                    AttributeValue::Paint(link.to_string(), fallback)
-                }
+            match parse_paint(value) {
+                Ok(paint) => paint,
                 Err(_) => {
                     warn!("Failed to parse fill value: '{}'. Fallback to black.", value);
-                    AttributeValue::Color(svgtypes::Color::black())
+                    AttributeValue::Color(svgtypes::Color::black().into())
                 }
             }
         }
 
         AId::Stroke => {
-            match svgtypes::Paint::from_str(value)? {
-                svgtypes::Paint::None => AttributeValue::None,
-                svgtypes::Paint::Inherit => unreachable!(),
-                svgtypes::Paint::CurrentColor => AttributeValue::CurrentColor,
-                svgtypes::Paint::Color(color) => AttributeValue::Color(color),
-                svgtypes::Paint::FuncIRI(link, fallback) => {
-                    AttributeValue::Paint(link.to_string(), fallback)
-                }
-            }
+            parse_paint(value)?
+        }
+
+        AId::ClipPath if value.starts_with("circle(") || value.starts_with("inset(") => {
+            // A CSS basic shape. Will be resolved during conversion,
+            // since it depends on the element's bounding box.
+            AttributeValue::String(value.to_string())
         }
 
           AId::ClipPath
@@ -453,7 +538,7 @@ fn parse_svg_attribute(
         }
 
         AId::Color => {
-            AttributeValue::Color(svgtypes::Color::from_str(value)?)
+            AttributeValue::Color(parse_color(value)?)
         }
 
           AId::FloodColor
@@ -461,7 +546,7 @@ fn parse_svg_attribute(
         | AId::StopColor => {
             match value {
                 "currentColor" => AttributeValue::CurrentColor,
-                _ => AttributeValue::Color(svgtypes::Color::from_str(value)?),
+                _ => AttributeValue::Color(parse_color(value)?),
             }
         }
 
@@ -477,7 +562,12 @@ fn parse_svg_attribute(
           AId::Transform
         | AId::GradientTransform
         | AId::PatternTransform => {
-            AttributeValue::Transform(svgtypes::Transform::from_str(value)?)
+            let ts = match svgtypes::Transform::from_str(value) {
+                Ok(ts) => ts,
+                Err(e) => parse_css_transform(value).map_err(|_| e)?,
+            };
+
+            AttributeValue::Transform(ts)
         }
 
         AId::FontSize => {
@@ -502,6 +592,13 @@ fn parse_svg_attribute(
             }
         }
 
+        AId::Kerning => {
+            match value {
+                "auto" => AttributeValue::String(value.to_string()),
+                _ => AttributeValue::Length(svgtypes::Length::from_str(value)?),
+            }
+        }
+
         AId::BaselineShift => {
             match value {
                 "baseline" | "sub" | "super" => AttributeValue::String(value.to_string()),
@@ -511,7 +608,7 @@ fn parse_svg_attribute(
 
         AId::Orient => {
             match value {
-                "auto" => AttributeValue::String(value.to_string()),
+                "auto" | "auto-start-reverse" => AttributeValue::String(value.to_string()),
                 _ => AttributeValue::Angle(svgtypes::Angle::from_str(value)?),
             }
         }
@@ -555,6 +652,134 @@ fn parse_number(value: &str) -> Result<f64, svgtypes::Error> {
     Ok(n)
 }
 
+/// Color keywords added by CSS Color 4 and missing in `svgtypes`.
+static EXTRA_COLOR_KEYWORDS: &[(&str, svgtypes::Color)] = &[
+    ("rebeccapurple", svgtypes::Color { red: 102, green: 51, blue: 153 }),
+];
+
+fn parse_extra_color_keyword(value: &str) -> Option<svgtypes::Color> {
+    let value = value.trim();
+    EXTRA_COLOR_KEYWORDS.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(value))
+        .map(|(_, color)| *color)
+}
+
+/// Parses a color, including an `rgba()` one.
+///
+/// The alpha is preserved and applied to the corresponding opacity during conversion,
+/// so it will be inherited along with the color.
+fn parse_color(value: &str) -> Result<tree::ColorA, svgtypes::Error> {
+    svgtypes::Color::from_str(value).map(tree::ColorA::from).or_else(|e| {
+        parse_extra_color_keyword(value).map(tree::ColorA::from)
+            .or_else(|| tree::ColorA::from_str(value).ok()).ok_or(e)
+    })
+}
+
+fn parse_paint(value: &str) -> Result<AttributeValue, svgtypes::Error> {
+    let paint = match svgtypes::Paint::from_str(value) {
+        Ok(paint) => paint,
+        Err(e) => return parse_color(value).map(AttributeValue::Color).map_err(|_| e),
+    };
+
+    Ok(match paint {
+        svgtypes::Paint::None => AttributeValue::None,
+        svgtypes::Paint::Inherit => unreachable!(),
+        svgtypes::Paint::CurrentColor => AttributeValue::CurrentColor,
+        svgtypes::Paint::Color(color) => AttributeValue::Color(color.into()),
+        svgtypes::Paint::FuncIRI(link, fallback) => {
+            AttributeValue::Paint(link.to_string(), fallback)
+        }
+    })
+}
+
+/// Parses a transform in the CSS syntax.
+///
+/// Unlike the SVG one, it allows angle and length units, requires commas between arguments
+/// and has a few extra functions, like `translateX`. Percentages are not supported.
+fn parse_css_transform(value: &str) -> Result<svgtypes::Transform, svgtypes::Error> {
+    let mut ts = svgtypes::Transform::default();
+    if value.trim() == "none" {
+        return Ok(ts);
+    }
+
+    let mut s = svgtypes::Stream::from(value);
+    s.skip_spaces();
+    while !s.at_end() {
+        let name = s.consume_ident();
+        s.consume_byte(b'(')?;
+
+        let mut args = Vec::new();
+        loop {
+            s.skip_spaces();
+            let n = s.parse_number()?;
+            let unit = s.consume_bytes(|_, c| c.is_ascii_alphabetic() || c == b'%');
+            args.push((n, unit));
+
+            s.skip_spaces();
+            if s.is_curr_byte_eq(b',') {
+                s.advance(1);
+            } else {
+                break;
+            }
+        }
+
+        s.consume_byte(b')')?;
+        s.skip_spaces();
+
+        let angle = |i: usize| -> Result<f64, svgtypes::Error> {
+            let (n, unit) = args[i];
+            match unit {
+                "" | "deg" => Ok(n),
+                "grad" => Ok(n * 0.9),
+                "rad" => Ok(n.to_degrees()),
+                "turn" => Ok(n * 360.0),
+                _ => Err(svgtypes::Error::InvalidValue),
+            }
+        };
+
+        let length = |i: usize| -> Result<f64, svgtypes::Error> {
+            match args[i] {
+                (n, "") | (n, "px") => Ok(n),
+                _ => Err(svgtypes::Error::InvalidValue),
+            }
+        };
+
+        let number = |i: usize| -> Result<f64, svgtypes::Error> {
+            match args[i] {
+                (n, "") => Ok(n),
+                _ => Err(svgtypes::Error::InvalidValue),
+            }
+        };
+
+        let ts2 = match (name, args.len()) {
+            ("matrix", 6) => svgtypes::Transform::new(
+                number(0)?, number(1)?, number(2)?, number(3)?, length(4)?, length(5)?,
+            ),
+            ("translate", 1) => svgtypes::Transform::new_translate(length(0)?, 0.0),
+            ("translate", 2) => svgtypes::Transform::new_translate(length(0)?, length(1)?),
+            ("translateX", 1) => svgtypes::Transform::new_translate(length(0)?, 0.0),
+            ("translateY", 1) => svgtypes::Transform::new_translate(0.0, length(0)?),
+            ("scale", 1) => svgtypes::Transform::new_scale(number(0)?, number(0)?),
+            ("scale", 2) => svgtypes::Transform::new_scale(number(0)?, number(1)?),
+            ("scaleX", 1) => svgtypes::Transform::new_scale(number(0)?, 1.0),
+            ("scaleY", 1) => svgtypes::Transform::new_scale(1.0, number(0)?),
+            ("rotate", 1) => svgtypes::Transform::new_rotate(angle(0)?),
+            ("skew", 1) => svgtypes::Transform::new_skew_x(angle(0)?),
+            ("skew", 2) => {
+                let (ax, ay) = (angle(0)?.to_radians(), angle(1)?.to_radians());
+                svgtypes::Transform::new(1.0, ay.tan(), ax.tan(), 1.0, 0.0, 0.0)
+            }
+            ("skewX", 1) => svgtypes::Transform::new_skew_x(angle(0)?),
+            ("skewY", 1) => svgtypes::Transform::new_skew_y(angle(0)?),
+            _ => return Err(svgtypes::Error::InvalidValue),
+        };
+
+        ts.append(&ts2);
+    }
+
+    Ok(ts)
+}
+
 #[inline(never)]
 fn parse_path(text: &str) -> tree::PathData {
     // Previous MoveTo coordinates.
@@ -789,6 +1014,140 @@ fn parse_enable_background(value: &str) -> Result<tree::EnableBackground, svgtyp
     }
 }
 
+/// Replaces `rem` lengths with `px` ones.
+///
+/// `svgtypes` doesn't support `rem` units, but unlike `em` they depend only on
+/// the root element's `font-size`, which is already parsed at this point.
+///
+/// Only length attributes are processed, so IDs, links and other strings are preserved.
+fn resolve_rem_units<'a>(
+    tag_name: EId,
+    aid: AId,
+    value: &'a str,
+    doc: &Document,
+    opt: &Options,
+) -> Cow<'a, str> {
+    if !is_length_attribute(tag_name, aid) || !value.contains("rem") {
+        return Cow::Borrowed(value);
+    }
+
+    let bytes = value.as_bytes();
+    let mut result = String::with_capacity(value.len());
+    let mut last = 0;
+    let mut root_font_size = None;
+    for (idx, _) in value.match_indices("rem") {
+        let is_unit_end = bytes.get(idx + 3).is_none_or(|c| !c.is_ascii_alphanumeric());
+
+        let mut start = idx;
+        while start > last && matches!(bytes[start - 1], b'0'..=b'9' | b'.') {
+            start -= 1;
+        }
+
+        if start == idx || !is_unit_end {
+            continue;
+        }
+
+        if start > last && matches!(bytes[start - 1], b'-' | b'+') {
+            start -= 1;
+        }
+
+        let n: f64 = match value[start..idx].parse() {
+            Ok(n) => n,
+            Err(_) => continue,
+        };
+
+        let font_size = *root_font_size.get_or_insert_with(|| resolve_root_font_size(doc, opt));
+        result.push_str(&value[last..start]);
+        result.push_str(&format!("{}px", n * font_size));
+        last = idx + 3;
+    }
+
+    if last == 0 {
+        return Cow::Borrowed(value);
+    }
+
+    result.push_str(&value[last..]);
+    Cow::Owned(result)
+}
+
+/// Returns the root element's `font-size`.
+///
+/// Named font sizes are not supported, and the default font size is used instead.
+fn resolve_root_font_size(doc: &Document, opt: &Options) -> f64 {
+    // When the root element itself is being parsed, it's not added to the tree yet.
+    let root = match doc.root().first_element_child() {
+        Some(root) => root,
+        None => return opt.font_size,
+    };
+
+    let length = match root.attribute::<svgtypes::Length>(AId::FontSize) {
+        Some(length) => length,
+        None => return opt.font_size,
+    };
+
+    let n = length.num;
+    match length.unit {
+        svgtypes::LengthUnit::Em => n * opt.font_size,
+        svgtypes::LengthUnit::Ex => n * opt.font_size / 2.0,
+        svgtypes::LengthUnit::Percent => n * opt.font_size / 100.0,
+        unit => units::convert_absolute(n, unit, units::resolve_dpi(AId::FontSize, opt)),
+    }
+}
+
+/// Checks that the attribute value is a length or a list of lengths.
+fn is_length_attribute(tag_name: EId, aid: AId) -> bool {
+    match aid {
+          AId::X | AId::Y
+        | AId::Dx | AId::Dy => !matches!(tag_name, EId::FePointLight | EId::FeSpotLight),
+          AId::X1 | AId::Y1
+        | AId::X2 | AId::Y2
+        | AId::R
+        | AId::Rx | AId::Ry
+        | AId::Cx | AId::Cy
+        | AId::Fx | AId::Fy
+        | AId::RefX | AId::RefY
+        | AId::Width | AId::Height
+        | AId::MarkerWidth | AId::MarkerHeight
+        | AId::StartOffset
+        | AId::TextLength
+        | AId::StrokeDasharray
+        | AId::StrokeDashoffset
+        | AId::StrokeWidth
+        | AId::FontSize
+        | AId::LetterSpacing
+        | AId::WordSpacing
+        | AId::Kerning
+        | AId::BaselineShift => true,
+        _ => false,
+    }
+}
+
+/// Removes whitespaces and quotes around the IRI inside `url()`.
+///
+/// `svgtypes` supports only the bare `url(#id)` form,
+/// while `url( '#id' )` is valid CSS and produced by some editors.
+fn normalize_func_iri(value: &str) -> Cow<'_, str> {
+    let trimmed = value.trim_start();
+    if !trimmed.starts_with("url(") {
+        return Cow::Borrowed(value);
+    }
+
+    let end = match trimmed.find(')') {
+        Some(idx) => idx,
+        None => return Cow::Borrowed(value),
+    };
+
+    let mut iri = trimmed[4..end].trim();
+    if iri.len() >= 2 {
+        let first = iri.as_bytes()[0];
+        if (first == b'\'' || first == b'"') && iri.as_bytes()[iri.len() - 1] == first {
+            iri = iri[1..iri.len() - 1].trim();
+        }
+    }
+
+    Cow::Owned(format!("url({}){}", iri, &trimmed[end + 1..]))
+}
+
 fn resolve_inherit(
     parent_id: NodeId,
     tag_name: EId,
@@ -822,7 +1181,9 @@ fn resolve_inherit(
 
     // Fallback to a default value if possible.
     let value = match aid {
-          AId::ImageRendering
+          AId::Clip
+        | AId::ImageRendering
+        | AId::Kerning
         | AId::ShapeRendering
         | AId::TextRendering => "auto",
 
@@ -834,13 +1195,15 @@ fn resolve_inherit(
         | AId::Mask
         | AId::Stroke
         | AId::StrokeDasharray
-        | AId::TextDecoration => "none",
+        | AId::TextDecoration
+        | AId::VectorEffect => "none",
 
           AId::FontStretch
         | AId::FontStyle
         | AId::FontVariant
         | AId::FontWeight
         | AId::LetterSpacing
+        | AId::PaintOrder
         | AId::WordSpacing => "normal",
 
           AId::Fill
@@ -861,6 +1224,7 @@ fn resolve_inherit(
         AId::Direction =>                   "ltr",
         AId::Display =>                     "inline",
         AId::FontSize =>                    "medium",
+        AId::MaskType =>                    "luminance",
         AId::StrokeDashoffset =>            "0",
         AId::StrokeLinecap =>               "butt",
         AId::StrokeLinejoin =>              "miter",
@@ -872,8 +1236,7 @@ fn resolve_inherit(
         _ => return false,
     };
 
-    doc.append_attribute(tag_name, aid, value);
-    true
+    doc.append_attribute(tag_name, aid, value)
 }
 
 fn resolve_href<'a>(
@@ -889,6 +1252,7 @@ fn parse_svg_use_element(
     origin: roxmltree::Node,
     parent_id: NodeId,
     style_sheet: &simplecss::StyleSheet,
+    opt: &Options,
     doc: &mut Document,
 ) -> Option<()> {
     let link = resolve_href(node)?;
@@ -899,14 +1263,7 @@ fn parse_svg_use_element(
         return None;
     }
 
-    let tag_name = parse_tag_name(link)?;
-
-    // TODO: this
-    // We don't support 'use' elements linked to 'svg' element.
-    if tag_name == EId::Svg {
-        warn!("'use' elements linked to an 'svg' element are not supported. Skipped.");
-        return None;
-    }
+    parse_tag_name(link)?;
 
     // Check that none of the linked node's children reference current `use` node
     // via other `use` node.
@@ -941,7 +1298,7 @@ fn parse_svg_use_element(
         return None;
     }
 
-    parse_xml_node(link, node, parent_id, style_sheet, true, doc);
+    parse_xml_node(link, node, parent_id, style_sheet, true, opt, doc);
     Some(())
 }
 
@@ -949,6 +1306,7 @@ fn parse_svg_text_element(
     parent: roxmltree::Node,
     parent_id: NodeId,
     style_sheet: &simplecss::StyleSheet,
+    opt: &Options,
     doc: &mut Document,
 ) {
     debug_assert_eq!(parent.tag_name().name(), "text");
@@ -963,7 +1321,7 @@ fn parse_svg_text_element(
         }
     };
 
-    parse_svg_text_element_impl(parent, parent_id, style_sheet, space, doc);
+    parse_svg_text_element_impl(parent, parent_id, style_sheet, space, opt, doc);
 
     trim_text_nodes(parent_id, space, doc);
 }
@@ -973,6 +1331,7 @@ fn parse_svg_text_element_impl(
     parent_id: NodeId,
     style_sheet: &simplecss::StyleSheet,
     space: XmlSpace,
+    opt: &Options,
     doc: &mut Document,
 ) {
     for node in parent.children() {
@@ -1005,7 +1364,7 @@ fn parse_svg_text_element_impl(
             is_tref = true;
         }
 
-        let node_id = parse_svg_element(node, parent_id, tag_name, style_sheet, false, doc);
+        let node_id = parse_svg_element(node, parent_id, tag_name, style_sheet, false, opt, doc);
         let space = get_xmlspace(doc, node_id, space);
 
         if is_tref {
@@ -1016,7 +1375,7 @@ fn parse_svg_text_element_impl(
                 }
             }
         } else {
-            parse_svg_text_element_impl(node, node_id, style_sheet, space, doc);
+            parse_svg_text_element_impl(node, node_id, style_sheet, space, opt, doc);
         }
     }
 }
@@ -1327,11 +1686,13 @@ fn fix_recursive_patterns(doc: &mut Document) {
     while let Some(node_id) = find_recursive_pattern(AId::Fill, doc) {
         let idx = doc.get(node_id).attribute_id(AId::Fill).unwrap();
         doc.attrs[idx.0].value = AttributeValue::None;
+        doc.recursive_links.push((node_id, AId::Fill));
     }
 
     while let Some(node_id) = find_recursive_pattern(AId::Stroke, doc) {
         let idx = doc.get(node_id).attribute_id(AId::Stroke).unwrap();
         doc.attrs[idx.0].value = AttributeValue::None;
+        doc.recursive_links.push((node_id, AId::Stroke));
     }
 }
 
@@ -1372,6 +1733,7 @@ fn fix_recursive_links(
     while let Some(node_id) = find_recursive_link(eid, aid, doc) {
         let idx = doc.get(node_id).attribute_id(aid).unwrap();
         doc.attrs[idx.0].value = AttributeValue::None;
+        doc.recursive_links.push((node_id, aid));
     }
 }
 
@@ -2,8 +2,10 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::borrow::Cow;
 use std::fmt;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 pub use svgtypes::{
     Align,
@@ -144,6 +146,95 @@ impl_enum_from_str!(Visibility,
 );
 
 
+/// A color with an alpha channel.
+///
+/// Unlike `Color`, which is used by paints and has a separate opacity,
+/// this color is used where there is no place to store an opacity,
+/// like an image background.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ColorA {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+impl ColorA {
+    /// Constructs a new `ColorA` from `red`, `green`, `blue` and `alpha` values.
+    #[inline]
+    pub fn new(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        ColorA { red, green, blue, alpha }
+    }
+
+    /// Returns a color without the alpha channel.
+    #[inline]
+    pub fn to_color(&self) -> Color {
+        Color::new(self.red, self.green, self.blue)
+    }
+}
+
+impl From<Color> for ColorA {
+    #[inline]
+    fn from(c: Color) -> Self {
+        ColorA::new(c.red, c.green, c.blue, 255)
+    }
+}
+
+impl FromStr for ColorA {
+    type Err = &'static str;
+
+    /// Parses a color in any format supported by `Color`
+    /// and an `rgba(<red>, <green>, <blue>, <alpha>)` color.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let text = match text.strip_prefix("rgba(") {
+            Some(text) => text,
+            None => return Color::from_str(text).map(ColorA::from).map_err(|_| "invalid color"),
+        };
+
+        let text = text.strip_suffix(')').ok_or("invalid color")?;
+
+        fn parse_channel(s: &str, max: f64) -> Result<f64, &'static str> {
+            let s = s.trim();
+            let n = if let Some(s) = s.strip_suffix('%') {
+                let n: f64 = s.parse().map_err(|_| "invalid color")?;
+                n / 100.0 * 255.0
+            } else {
+                let n: f64 = s.parse().map_err(|_| "invalid color")?;
+                n * 255.0 / max
+            };
+
+            Ok(f64_bound(0.0, n, 255.0).round())
+        }
+
+        let values: Vec<&str> = text.split(',').collect();
+        if values.len() != 4 {
+            return Err("invalid color");
+        }
+
+        Ok(ColorA::new(
+            parse_channel(values[0], 255.0)? as u8,
+            parse_channel(values[1], 255.0)? as u8,
+            parse_channel(values[2], 255.0)? as u8,
+            // Alpha is a number in a 0..1 range.
+            parse_channel(values[3], 1.0)? as u8,
+        ))
+    }
+}
+
+impl fmt::Display for ColorA {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.alpha == 255 {
+            write!(f, "{}", self.to_color())
+        } else {
+            write!(f, "rgba({}, {}, {}, {})", self.red, self.green, self.blue,
+                   self.alpha as f64 / 255.0)
+        }
+    }
+}
+
+
 /// A paint style.
 ///
 /// `paint` value type in the SVG.
@@ -211,6 +302,11 @@ pub struct Stroke {
     pub width: StrokeWidth,
     pub linecap: LineCap,
     pub linejoin: LineJoin,
+    /// Indicates that the stroke width and dashing are set in device pixels
+    /// and are not affected by transforms.
+    ///
+    /// Set by `vector-effect="non-scaling-stroke"`.
+    pub non_scaling: bool,
 }
 
 impl Default for Stroke {
@@ -226,10 +322,40 @@ impl Default for Stroke {
             width: StrokeWidth::default(),
             linecap: LineCap::default(),
             linejoin: LineJoin::default(),
+            non_scaling: false,
         }
     }
 }
 
+impl Stroke {
+    /// Returns a copy of the stroke with `non_scaling` applied.
+    ///
+    /// The width and dashing are divided by the scale of the transform `ts`,
+    /// which maps the stroke user space to device pixels.
+    /// This is exact only for transforms with a uniform scale.
+    pub fn resolve_non_scaling(&self, ts: Transform) -> Cow<'_, Stroke> {
+        if !self.non_scaling {
+            return Cow::Borrowed(self);
+        }
+
+        let scale = (ts.a * ts.d - ts.b * ts.c).abs().sqrt();
+        if !scale.is_normal() {
+            return Cow::Borrowed(self);
+        }
+
+        let mut stroke = self.clone();
+        stroke.width = StrokeWidth::new(stroke.width.value() / scale);
+        stroke.dashoffset = (stroke.dashoffset as f64 / scale) as f32;
+        if let Some(ref mut list) = stroke.dasharray {
+            for n in list.iter_mut() {
+                *n /= scale;
+            }
+        }
+
+        Cow::Owned(stroke)
+    }
+}
+
 
 /// View box.
 #[derive(Clone, Copy, Debug)]
@@ -291,7 +417,8 @@ pub enum ImageData {
     /// Preprocessor will check that the file exist, but because it can be removed later,
     /// so there is no guarantee that this path is valid.
     ///
-    /// The path may be relative.
+    /// The path is resolved relative to `Options::path`,
+    /// but can still be relative when `Options::path` is not set or is relative itself.
     Path(PathBuf),
 
     /// Image raw data.
@@ -492,6 +619,67 @@ impl_enum_from_str!(ShapeRendering,
 impl_from_str!(ShapeRendering);
 
 
+/// A mask type.
+///
+/// `mask-type` attribute in the SVG.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MaskType {
+    Luminance,
+    Alpha,
+}
+
+impl_enum_default!(MaskType, Luminance);
+
+impl_enum_from_str!(MaskType,
+    "luminance" => MaskType::Luminance,
+    "alpha"     => MaskType::Alpha
+);
+
+impl_from_str!(MaskType);
+
+
+/// A paint order.
+///
+/// `paint-order` attribute in the SVG.
+///
+/// Only the order of the fill and the stroke is preserved here.
+/// Markers are converted into separate elements, which are placed
+/// according to the `paint-order` during the conversion.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PaintOrder {
+    FillAndStroke,
+    StrokeAndFill,
+}
+
+impl_enum_default!(PaintOrder, FillAndStroke);
+
+impl crate::svgtree::EnumFromStr for PaintOrder {
+    fn enum_from_str(s: &str) -> Option<Self> {
+        if s == "normal" {
+            return Some(PaintOrder::FillAndStroke);
+        }
+
+        // Omitted values are painted after the specified one in the default order.
+        // So the stroke is painted first only when it precedes the fill or the fill is omitted.
+        let mut order = None;
+        for value in s.split_whitespace() {
+            match value {
+                "fill" => { order.get_or_insert(PaintOrder::FillAndStroke); }
+                "stroke" => { order.get_or_insert(PaintOrder::StrokeAndFill); }
+                "markers" => {}
+                _ => return None,
+            }
+        }
+
+        order.or(Some(PaintOrder::FillAndStroke))
+    }
+}
+
+impl_from_str!(PaintOrder);
+
+
 /// A text rendering method.
 ///
 /// `text-rendering` attribute in the SVG.
@@ -526,9 +714,15 @@ pub enum ImageRendering {
 
 impl_enum_default!(ImageRendering, OptimizeQuality);
 
+// CSS Images Module Level 3 values are also accepted,
+// since `image-rendering` can be set via CSS.
 impl_enum_from_str!(ImageRendering,
     "optimizeQuality"   => ImageRendering::OptimizeQuality,
-    "optimizeSpeed"     => ImageRendering::OptimizeSpeed
+    "optimizeSpeed"     => ImageRendering::OptimizeSpeed,
+    "smooth"            => ImageRendering::OptimizeQuality,
+    "high-quality"      => ImageRendering::OptimizeQuality,
+    "crisp-edges"       => ImageRendering::OptimizeSpeed,
+    "pixelated"         => ImageRendering::OptimizeSpeed
 );
 
 impl_from_str!(ImageRendering);
@@ -540,3 +734,29 @@ impl_from_str!(ImageRendering);
 #[derive(Clone, Copy, Debug)]
 #[allow(missing_docs)]
 pub struct EnableBackground(pub Option<Rect>);
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_color_a_1() {
+        assert_eq!("#ff0000".parse::<ColorA>(), Ok(ColorA::new(255, 0, 0, 255)));
+    }
+
+    #[test]
+    fn parse_color_a_2() {
+        assert_eq!("rgba(255, 0, 0, 0.5)".parse::<ColorA>(), Ok(ColorA::new(255, 0, 0, 128)));
+    }
+
+    #[test]
+    fn parse_color_a_3() {
+        assert_eq!("rgba(100%, 0%, 50%, 100%)".parse::<ColorA>(), Ok(ColorA::new(255, 0, 128, 255)));
+    }
+
+    #[test]
+    fn parse_color_a_4() {
+        assert!("rgba(255, 0, 0)".parse::<ColorA>().is_err());
+    }
+}
@@ -7,14 +7,14 @@ use std::io::Write;
 use std::ops::Deref;
 
 use svgtypes::WriteBuffer;
-use xmlwriter::XmlWriter;
 
 use super::*;
+use super::writer::XmlWriter;
 use crate::{geom::*, svgtree::{EId, AId}, IsDefault};
 
 
-pub fn convert(tree: &Tree, opt: XmlOptions) -> String {
-    let mut xml = XmlWriter::new(opt);
+pub fn convert(tree: &Tree, opt: XmlOptions, sink: &mut dyn Write) -> std::io::Result<()> {
+    let mut xml = XmlWriter::new(sink, opt);
 
     let svg_node = tree.svg_node();
 
@@ -29,6 +29,10 @@ pub fn convert(tree: &Tree, opt: XmlOptions) -> String {
     xml.write_attribute("xmlns:usvg", "https://github.com/RazrFalcon/resvg");
     xml.write_attribute("usvg:version", env!("CARGO_PKG_VERSION"));
 
+    if let Some(ref metadata) = svg_node.metadata {
+        write_metadata(metadata, &mut xml);
+    }
+
     xml.start_svg_element(EId::Defs);
     conv_defs(tree, &mut xml);
     xml.end_element();
@@ -38,10 +42,68 @@ pub fn convert(tree: &Tree, opt: XmlOptions) -> String {
     xml.end_document()
 }
 
+/// Writes the raw `metadata` XML.
+///
+/// The metadata is parsed and written element by element, so it's formatted
+/// just like the rest of the output. Comments and processing instructions are skipped.
+fn write_metadata(metadata: &str, xml: &mut XmlWriter) {
+    match roxmltree::Document::parse(metadata) {
+        Ok(doc) => write_metadata_element(doc.root_element(), false, xml),
+        Err(e) => warn!("Failed to parse metadata cause {}.", e),
+    }
+}
+
+fn write_metadata_element(node: roxmltree::Node, preserve_whitespaces: bool, xml: &mut XmlWriter) {
+    let qualified_name = |ns: Option<&str>, name: &str| {
+        match ns.and_then(|uri| node.lookup_prefix(uri)) {
+            Some(prefix) => format!("{}:{}", prefix, name),
+            None => name.to_string(),
+        }
+    };
+
+    let tag_name = node.tag_name();
+    xml.start_element(&qualified_name(tag_name.namespace(), tag_name.name()));
+
+    // `namespaces()` returns all namespaces in scope, so we have to skip the inherited ones.
+    let parent_namespaces = node.parent_element().map(|n| n.namespaces()).unwrap_or(&[]);
+    for ns in node.namespaces() {
+        if parent_namespaces.contains(ns) {
+            continue;
+        }
+
+        match ns.name() {
+            Some(prefix) => xml.write_attribute(&format!("xmlns:{}", prefix), ns.uri()),
+            None => xml.write_attribute("xmlns", ns.uri()),
+        }
+    }
+
+    for attr in node.attributes() {
+        xml.write_attribute(&qualified_name(attr.namespace(), attr.name()), attr.value());
+    }
+
+    // Elements with a text content are written without any formatting,
+    // otherwise the formatting would become a part of the text.
+    let has_text = node.children().any(|n| n.is_text() && !n.text().unwrap_or("").trim().is_empty());
+    let preserve_children = preserve_whitespaces || has_text;
+    xml.set_preserve_whitespaces(preserve_children);
+
+    for child in node.children() {
+        if child.is_element() {
+            write_metadata_element(child, preserve_children, xml);
+        } else if child.is_text() && preserve_children {
+            xml.write_text(child.text().unwrap_or(""));
+        }
+    }
+
+    xml.end_element();
+    xml.set_preserve_whitespaces(preserve_whitespaces);
+}
+
 fn conv_defs(
     tree: &Tree,
     xml: &mut XmlWriter,
 ) {
+    // Defs are written in the `Tree::defs` order, which is stable.
     for n in tree.defs().children() {
         match *n.borrow() {
             NodeKind::LinearGradient(ref lg) => {
@@ -86,6 +148,10 @@ fn conv_defs(
                 xml.write_units(AId::MaskContentUnits, mask.content_units, Units::UserSpaceOnUse);
                 xml.write_rect_attrs(mask.rect);
 
+                if mask.kind == MaskType::Alpha {
+                    xml.write_svg_attribute(AId::MaskType, "alpha");
+                }
+
                 if let Some(ref id) = mask.mask {
                     xml.write_func_iri(AId::Mask, id);
                 }
@@ -139,6 +205,16 @@ fn conv_defs(
                             xml.write_svg_attribute(AId::Result, &fe.result);
                             xml.end_element();
                         }
+                        FilterKind::FePassThrough(ref pass) => {
+                            // `feMerge` with a single input is the closest SVG equivalent.
+                            xml.start_svg_element(EId::FeMerge);
+                            xml.write_filter_primitive_attrs(fe);
+                            xml.write_svg_attribute(AId::Result, &fe.result);
+                            xml.start_svg_element(EId::FeMergeNode);
+                            xml.write_filter_input(AId::In, &pass.input);
+                            xml.end_element();
+                            xml.end_element();
+                        }
                         FilterKind::FeBlend(ref blend) => {
                             xml.start_svg_element(EId::FeBlend);
                             xml.write_filter_primitive_attrs(fe);
@@ -511,7 +587,7 @@ trait XmlWriterExt {
     fn write_image_data(&mut self, data: &ImageData, format: ImageFormat);
 }
 
-impl XmlWriterExt for XmlWriter {
+impl XmlWriterExt for XmlWriter<'_> {
     #[inline(never)]
     fn start_svg_element(&mut self, id: EId) {
         self.start_element(id.to_str());
@@ -667,18 +743,19 @@ impl XmlWriterExt for XmlWriter {
                 self.write_attribute("xlink:href", &path.to_str().unwrap());
             }
             ImageData::Raw(ref data) => {
-                self.write_attribute_raw("xlink:href", |buf| {
-                    buf.extend_from_slice(b"data:image/");
-                    buf.extend_from_slice(match format {
+                // Images can be pretty big, so we are encoding them directly into the output.
+                self.write_attribute_stream("xlink:href", |mut sink| {
+                    sink.write_all(b"data:image/")?;
+                    sink.write_all(match format {
                         ImageFormat::PNG => b"png",
                         ImageFormat::JPEG => b"jpg",
                         ImageFormat::SVG => b"svg+xml",
-                    });
-                    buf.extend_from_slice(b";base64, ");
+                    })?;
+                    sink.write_all(b";base64, ")?;
 
-                    let mut enc = base64::write::EncoderWriter::new(buf, base64::STANDARD);
-                    enc.write_all(data).unwrap();
-                    enc.finish().unwrap();
+                    let mut enc = base64::write::EncoderWriter::new(&mut sink, base64::STANDARD);
+                    enc.write_all(data)?;
+                    enc.finish()
                 });
             }
         }
@@ -744,6 +821,10 @@ fn write_path(
     write_fill(&path.fill, is_clip_path, xml);
     write_stroke(&path.stroke, xml);
 
+    if path.paint_order == PaintOrder::StrokeAndFill {
+        xml.write_svg_attribute(AId::PaintOrder, "stroke");
+    }
+
     xml.write_visibility(path.visibility);
 
     match path.rendering_mode {
@@ -872,6 +953,10 @@ fn write_stroke(
         if let Some(ref array) = stroke.dasharray {
             xml.write_numbers(AId::StrokeDasharray, array);
         }
+
+        if stroke.non_scaling {
+            xml.write_svg_attribute(AId::VectorEffect, "non-scaling-stroke");
+        }
     } else {
         // Always set `stroke` to `none` to override the parent value.
         // In 99.9% of the cases it's redundant, but a group with `filter` with `StrokePaint`
@@ -0,0 +1,292 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A geometric hit testing.
+//!
+//! Only the geometry is checked, so masks, filters, opacity
+//! and transparent paint are ignored. Which is the same as
+//! `pointer-events="visiblePainted"` with fully opaque paint.
+//! Since fill and stroke are checked together, `paint-order` doesn't matter.
+
+use super::*;
+use crate::{f64_bound, FuzzyEq, TransformExt, TransformFromBBox};
+
+/// The amount of line segments a curve will be flattened into.
+const CURVE_STEPS: usize = 16;
+
+
+pub fn element_at(tree: &Tree, x: f64, y: f64) -> Option<String> {
+    let node = node_at(tree, &tree.root(), x, y)?;
+    node.ancestors().map(|n| n.id().to_string()).find(|id| !id.is_empty())
+}
+
+/// Returns the topmost node under the point.
+///
+/// Children are checked in the reverse order, because the last one is rendered on top.
+fn node_at(tree: &Tree, parent: &Node, x: f64, y: f64) -> Option<Node> {
+    for node in parent.children().rev() {
+        match *node.borrow() {
+            NodeKind::Group(ref g) => {
+                if let Some(ref id) = g.clip_path {
+                    match to_local(&node, x, y) {
+                        Some((lx, ly)) if is_clipped_in(tree, id, group_bbox(&node), lx, ly) => {}
+                        _ => continue,
+                    }
+                }
+
+                if let Some(node) = node_at(tree, &node, x, y) {
+                    return Some(node);
+                }
+            }
+            NodeKind::Path(ref path) => {
+                if path.visibility != Visibility::Visible {
+                    continue;
+                }
+
+                if let Some((lx, ly)) = to_local(&node, x, y) {
+                    if path_contains(&node, path, lx, ly) {
+                        return Some(node.clone());
+                    }
+                }
+            }
+            NodeKind::Image(ref img) => {
+                if img.visibility != Visibility::Visible {
+                    continue;
+                }
+
+                if let Some((lx, ly)) = to_local(&node, x, y) {
+                    if img.view_box.rect.contains(lx, ly) {
+                        return Some(node.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Returns a transform from the node's coordinates into the root ones.
+fn node_transform(node: &Node) -> Transform {
+    let mut ts = node.abs_transform();
+    ts.append(&node.transform());
+    ts
+}
+
+/// Maps a point from the root coordinates into the node's coordinates.
+fn to_local(node: &Node, x: f64, y: f64) -> Option<(f64, f64)> {
+    Some(node_transform(node).invert()?.apply(x, y))
+}
+
+/// Returns a group bbox in the group's coordinates.
+fn group_bbox(node: &Node) -> Option<Rect> {
+    let mut bbox = Rect::new_bbox();
+    for child in node.children() {
+        if let Some(c_bbox) = calc_node_bbox(&child, Transform::default()) {
+            bbox = bbox.expand(c_bbox);
+        }
+    }
+
+    if bbox.fuzzy_ne(&Rect::new_bbox()) { Some(bbox) } else { None }
+}
+
+/// Checks that the point is inside the clip path.
+///
+/// The point must be in the coordinates of the clipped element.
+fn is_clipped_in(tree: &Tree, id: &str, bbox: Option<Rect>, x: f64, y: f64) -> bool {
+    let clip_node = match tree.defs_by_id(id) {
+        Some(node) => node,
+        None => return true,
+    };
+
+    let cp = match *clip_node.borrow() {
+        NodeKind::ClipPath(ref cp) => cp.clone(),
+        _ => return true,
+    };
+
+    let mut ts = cp.transform;
+    if cp.units == Units::ObjectBoundingBox {
+        match bbox {
+            Some(bbox) => ts.append(&Transform::from_bbox(bbox)),
+            None => return false,
+        }
+    }
+
+    let (cx, cy) = match ts.invert() {
+        Some(ts) => ts.apply(x, y),
+        None => return false,
+    };
+
+    let inside = clip_node.children().any(|child| {
+        let (lx, ly) = match child.transform().invert() {
+            Some(ts) => ts.apply(cx, cy),
+            None => return false,
+        };
+
+        match *child.borrow() {
+            NodeKind::Path(ref path) => fill_contains(path, lx, ly),
+            NodeKind::Group(ref g) => {
+                // A `clipPath` child with its own `clip-path` is stored
+                // as a group with a single path.
+                let is_path_inside = match child.first_child() {
+                    Some(ref node) => match *node.borrow() {
+                        NodeKind::Path(ref path) => {
+                            match node.transform().invert() {
+                                Some(ts) => {
+                                    let (px, py) = ts.apply(lx, ly);
+                                    fill_contains(path, px, py)
+                                }
+                                None => false,
+                            }
+                        }
+                        _ => false,
+                    },
+                    None => false,
+                };
+
+                is_path_inside && match g.clip_path {
+                    Some(ref id) => is_clipped_in(tree, id, bbox, lx, ly),
+                    None => true,
+                }
+            }
+            _ => false,
+        }
+    });
+
+    // A nested `clip-path` uses the same coordinates as the clipped element.
+    inside && match cp.clip_path {
+        Some(ref id) => is_clipped_in(tree, id, bbox, x, y),
+        None => true,
+    }
+}
+
+fn path_contains(node: &Node, path: &Path, x: f64, y: f64) -> bool {
+    if path.fill.is_some() && fill_contains(path, x, y) {
+        return true;
+    }
+
+    if let Some(ref stroke) = path.stroke {
+        // The root coordinates are treated as device pixels for a non-scaling stroke.
+        let stroke = stroke.resolve_non_scaling(node_transform(node));
+        return stroke_contains(&path.data, stroke.width.value() / 2.0, x, y);
+    }
+
+    false
+}
+
+fn fill_contains(path: &Path, x: f64, y: f64) -> bool {
+    let rule = path.fill.as_ref().map(|f| f.rule).unwrap_or(FillRule::NonZero);
+
+    let mut winding = 0;
+    for subpath in flatten(&path.data) {
+        // A fill is always closed.
+        let first = subpath[0];
+        let last = *subpath.last().unwrap();
+        for (p1, p2) in subpath.windows(2).map(|w| (w[0], w[1])).chain(Some((last, first))) {
+            winding += crossing(p1, p2, x, y);
+        }
+    }
+
+    match rule {
+        FillRule::NonZero => winding != 0,
+        FillRule::EvenOdd => winding % 2 != 0,
+    }
+}
+
+/// Returns a signed winding number contribution of a segment.
+fn crossing(p1: (f64, f64), p2: (f64, f64), x: f64, y: f64) -> i32 {
+    let is_left = (p2.0 - p1.0) * (y - p1.1) - (x - p1.0) * (p2.1 - p1.1);
+    if p1.1 <= y {
+        if p2.1 > y && is_left > 0.0 {
+            return 1;
+        }
+    } else if p2.1 <= y && is_left < 0.0 {
+        return -1;
+    }
+
+    0
+}
+
+/// Checks that the point is within the `half_width` distance from the path.
+///
+/// Line joins and caps are approximated as round ones.
+fn stroke_contains(data: &PathData, half_width: f64, x: f64, y: f64) -> bool {
+    flatten(data).iter().any(|subpath| {
+        if subpath.len() == 1 {
+            return false;
+        }
+
+        subpath.windows(2).any(|w| distance_to_segment(w[0], w[1], x, y) <= half_width)
+    })
+}
+
+fn distance_to_segment(p1: (f64, f64), p2: (f64, f64), x: f64, y: f64) -> f64 {
+    let dx = p2.0 - p1.0;
+    let dy = p2.1 - p1.1;
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 > 0.0 {
+        f64_bound(0.0, ((x - p1.0) * dx + (y - p1.1) * dy) / len2, 1.0)
+    } else {
+        0.0
+    };
+
+    let px = p1.0 + t * dx - x;
+    let py = p1.1 + t * dy - y;
+    (px * px + py * py).sqrt()
+}
+
+/// Converts a path into a list of polylines.
+///
+/// A closed subpath ends with its first point.
+fn flatten(data: &PathData) -> Vec<Vec<(f64, f64)>> {
+    let mut list = Vec::new();
+    let mut points: Vec<(f64, f64)> = Vec::new();
+    let mut prev = (0.0, 0.0);
+    for seg in data.iter() {
+        match *seg {
+            PathSegment::MoveTo { x, y } => {
+                if !points.is_empty() {
+                    list.push(std::mem::take(&mut points));
+                }
+
+                points.push((x, y));
+                prev = (x, y);
+            }
+            PathSegment::LineTo { x, y } => {
+                points.push((x, y));
+                prev = (x, y);
+            }
+            PathSegment::CurveTo { x1, y1, x2, y2, x, y } => {
+                for i in 1..=CURVE_STEPS {
+                    let t = i as f64 / CURVE_STEPS as f64;
+                    let mt = 1.0 - t;
+                    let a = mt * mt * mt;
+                    let b = 3.0 * mt * mt * t;
+                    let c = 3.0 * mt * t * t;
+                    let d = t * t * t;
+                    points.push((
+                        a * prev.0 + b * x1 + c * x2 + d * x,
+                        a * prev.1 + b * y1 + c * y2 + d * y,
+                    ));
+                }
+
+                prev = (x, y);
+            }
+            PathSegment::ClosePath => {
+                if let Some(&first) = points.first() {
+                    points.push(first);
+                    prev = first;
+                    list.push(std::mem::replace(&mut points, vec![first]));
+                }
+            }
+        }
+    }
+
+    if points.len() > 1 {
+        list.push(points);
+    }
+
+    list
+}
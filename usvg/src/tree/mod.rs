@@ -4,23 +4,28 @@
 
 //! Implementation of the nodes tree.
 
+use std::borrow::Cow;
 use std::cell::Ref;
 use std::path;
 
-pub use self::{nodes::*, attributes::*, pathdata::*};
+pub use self::{nodes::*, attributes::*, pathdata::*, stats::Stats};
 use crate::{svgtree, Rect, Error, Options, XmlOptions};
 
 mod attributes;
 mod export;
+mod hit_test;
 mod nodes;
 mod numbers;
 mod pathdata;
+mod stats;
+mod writer;
 
 /// Basic traits for tree manipulations.
 pub mod prelude {
     pub use crate::IsDefault;
     pub use crate::IsValidLength;
     pub use crate::TransformFromBBox;
+    pub use crate::TransformExt;
     pub use crate::tree::FuzzyEq;
     pub use crate::tree::FuzzyZero;
     pub use super::NodeExt;
@@ -42,21 +47,60 @@ impl Tree {
     ///
     /// Can contain an SVG string or a gzip compressed data.
     pub fn from_data(data: &[u8], opt: &Options) -> Result<Self, Error> {
-        if data.starts_with(&[0x1f, 0x8b]) {
-            let text = deflate(data)?;
-            Self::from_str(&text, opt)
-        } else {
-            let text = ::std::str::from_utf8(data).map_err(|_| Error::NotAnUtf8Str)?;
-            Self::from_str(text, opt)
-        }
+        Self::from_str(&data_to_str(data)?, opt)
+    }
+
+    /// Parses `Tree` from a reader.
+    ///
+    /// Can contain an SVG string or a gzip compressed data.
+    pub fn from_reader<R: std::io::Read>(mut reader: R, opt: &Options) -> Result<Self, Error> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).map_err(|_| Error::FileOpenFailed)?;
+        Self::from_data(&data, opt)
     }
 
     /// Parses `Tree` from the SVG string.
     pub fn from_str(text: &str, opt: &Options) -> Result<Self, Error> {
-        let doc = svgtree::Document::parse(text).map_err(Error::ParsingFailed)?;
+        let _guard = crate::set_warning_handler(opt.warning_handler.as_ref());
+        let doc = svgtree::Document::parse(text, opt).map_err(Error::ParsingFailed)?;
         Self::from_dom(doc, &opt)
     }
 
+    /// Parses the SVG string and passes the resulting `Tree` to `f` in chunks.
+    ///
+    /// Each top-level element is converted and passed separately, inside a `Tree`
+    /// that contains only this element and the required `defs`.
+    /// After `f` returns, the element is removed from the tree.
+    /// Rendering all chunks in order onto the same canvas produces the same image
+    /// as rendering the `Tree` returned by `from_str`, unless `enable-background`
+    /// is used across top-level elements.
+    ///
+    /// Useful for very large documents with a lot of top-level elements,
+    /// since the output tree never holds more than one of them at a time.
+    /// The input document itself is still parsed at once.
+    pub fn from_str_chunked<F: FnMut(&Tree)>(
+        text: &str,
+        opt: &Options,
+        f: F,
+    ) -> Result<(), Error> {
+        let _guard = crate::set_warning_handler(opt.warning_handler.as_ref());
+        let doc = svgtree::Document::parse(text, opt).map_err(Error::ParsingFailed)?;
+        super::convert::convert_doc_chunked(&doc, opt, f)
+    }
+
+    /// Parses the SVG data and passes the resulting `Tree` to `f` in chunks.
+    ///
+    /// Can contain an SVG string or a gzip compressed data.
+    ///
+    /// See `from_str_chunked` for details.
+    pub fn from_data_chunked<F: FnMut(&Tree)>(
+        data: &[u8],
+        opt: &Options,
+        f: F,
+    ) -> Result<(), Error> {
+        Self::from_str_chunked(&data_to_str(data)?, opt, f)
+    }
+
     /// Parses `Tree` from the `svgdom::Document`.
     ///
     /// An empty `Tree` will be returned on any error.
@@ -102,6 +146,10 @@ impl Tree {
     }
 
     /// Returns the `Defs` node.
+    ///
+    /// `Defs` children are stored in the insertion order, which during conversion
+    /// is the order in which elements are first referenced in the document.
+    /// So the order depends only on the input and is stable between runs.
     #[inline]
     pub fn defs(&self) -> Node {
         self.root.first_child().unwrap()
@@ -114,6 +162,8 @@ impl Tree {
     }
 
     /// Appends `NodeKind` to the `Defs` node.
+    ///
+    /// The new node is always added after existing ones.
     pub fn append_to_defs(&mut self, kind: NodeKind) -> Node {
         debug_assert!(self.defs_by_id(kind.id()).is_none(),
                       "Element #{} already exists in 'defs'.", kind.id());
@@ -134,7 +184,11 @@ impl Tree {
         None
     }
 
-    /// Returns renderable node by ID.
+    /// Returns a node by ID.
+    ///
+    /// Both renderable and `Defs` nodes are checked. Renderable nodes are checked first,
+    /// so when an ID is used by both, the renderable node is returned.
+    /// Use `is_in_defs` to check the kind of the returned node.
     ///
     /// If an empty ID is provided, than this method will always return `None`.
     /// Even if tree has nodes with empty ID.
@@ -143,21 +197,118 @@ impl Tree {
             return None;
         }
 
-        for node in self.root().descendants() {
-            if !self.is_in_defs(&node) {
-                if &*node.id() == id {
-                    return Some(node);
+        // The `Defs` node is always the first child.
+        self.root.children().skip(1)
+            .flat_map(|node| node.descendants())
+            .chain(self.defs().descendants())
+            .find(|node| &*node.id() == id)
+    }
+
+    /// Returns an ID of the topmost element at the specified point.
+    ///
+    /// The point is in the root element coordinates, aka `viewBox` ones.
+    ///
+    /// Fill, stroke, transforms and clip paths are taken into account,
+    /// but the hit testing is purely geometric, so opacity, masks and filters are ignored.
+    /// Strokes are checked with round joins and caps.
+    ///
+    /// When the hit element doesn't have an ID, the closest ancestor's ID is returned.
+    /// Returns `None` when nothing was hit or no ID was found.
+    pub fn element_at(&self, x: f64, y: f64) -> Option<String> {
+        hit_test::element_at(self, x, y)
+    }
+
+    /// Replaces all fill, stroke, gradient stop, `flood-color` and `lighting-color` colors
+    /// using the provided function.
+    ///
+    /// Can be used to recolor an image, like an icon for a dark theme.
+    ///
+    /// All nodes are processed, including the `Defs` ones. Since paint servers are
+    /// referenced by ID, each gradient is processed only once, even when it's used
+    /// by multiple elements.
+    pub fn map_colors<F: FnMut(Color) -> Color>(&mut self, mut f: F) {
+        fn map_paint<F: FnMut(Color) -> Color>(paint: &mut Paint, f: &mut F) {
+            if let Paint::Color(ref mut c) = *paint {
+                *c = f(*c);
+            }
+        }
+
+        for mut node in self.root.descendants() {
+            match *node.borrow_mut() {
+                NodeKind::Path(ref mut path) => {
+                    if let Some(ref mut fill) = path.fill {
+                        map_paint(&mut fill.paint, &mut f);
+                    }
+
+                    if let Some(ref mut stroke) = path.stroke {
+                        map_paint(&mut stroke.paint, &mut f);
+                    }
+                }
+                NodeKind::LinearGradient(ref mut lg) => {
+                    for stop in &mut lg.base.stops {
+                        stop.color = f(stop.color);
+                    }
+                }
+                NodeKind::RadialGradient(ref mut rg) => {
+                    for stop in &mut rg.base.stops {
+                        stop.color = f(stop.color);
+                    }
+                }
+                NodeKind::Filter(ref mut filter) => {
+                    for primitive in &mut filter.children {
+                        match primitive.kind {
+                            FilterKind::FeFlood(ref mut fe) => {
+                                fe.color = f(fe.color);
+                            }
+                            FilterKind::FeDiffuseLighting(ref mut fe) => {
+                                fe.lighting_color = f(fe.lighting_color);
+                            }
+                            FilterKind::FeSpecularLighting(ref mut fe) => {
+                                fe.lighting_color = f(fe.lighting_color);
+                            }
+                            _ => {}
+                        }
+                    }
                 }
+                NodeKind::Group(ref mut g) => {
+                    // Used by the `FillPaint` and `StrokePaint` filter inputs.
+                    if let Some(ref mut paint) = g.filter_fill {
+                        map_paint(paint, &mut f);
+                    }
+
+                    if let Some(ref mut paint) = g.filter_stroke {
+                        map_paint(paint, &mut f);
+                    }
+                }
+                _ => {}
             }
         }
+    }
 
-        None
+    /// Returns the tree content summary.
+    ///
+    /// All nodes are counted, including the `Defs` ones.
+    pub fn stats(&self) -> Stats {
+        stats::calc(self)
     }
 
     /// Converts an SVG.
     #[inline]
     pub fn to_string(&self, opt: XmlOptions) -> String {
-        export::convert(self, opt)
+        let mut data = Vec::new();
+        // Writing into a `Vec` cannot fail.
+        export::convert(self, opt, &mut data).unwrap();
+        // The output contains only UTF-8 strings and base64 data.
+        String::from_utf8(data).unwrap()
+    }
+
+    /// Writes an SVG into the `writer`.
+    ///
+    /// Produces the same output as `to_string`, but the data is written
+    /// as it is generated, so the whole SVG is never kept in memory.
+    /// This includes the base64 encoded images.
+    pub fn write_to<W: std::io::Write>(&self, mut writer: W, opt: XmlOptions) -> std::io::Result<()> {
+        export::convert(self, opt, &mut writer)
     }
 }
 
@@ -286,6 +437,15 @@ pub fn load_svg_file(path: &path::Path) -> Result<String, Error> {
     }
 }
 
+/// Decompresses the data if needed.
+fn data_to_str(data: &[u8]) -> Result<Cow<'_, str>, Error> {
+    if data.starts_with(&[0x1f, 0x8b]) {
+        deflate(data).map(Cow::Owned)
+    } else {
+        std::str::from_utf8(data).map(Cow::Borrowed).map_err(|_| Error::NotAnUtf8Str)
+    }
+}
+
 fn deflate(data: &[u8]) -> Result<String, Error> {
     use std::io::Read;
 
@@ -296,7 +456,7 @@ fn deflate(data: &[u8]) -> Result<String, Error> {
     Ok(decoded)
 }
 
-fn calc_node_bbox(
+pub(crate) fn calc_node_bbox(
     node: &Node,
     ts: Transform,
 ) -> Option<Rect> {
@@ -2,16 +2,102 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::hash::{Hash, Hasher};
+use std::mem;
 use std::ops::Deref;
 use std::rc::Rc;
 
 use crate::geom::*;
 use super::attributes::*;
-use super::pathdata::PathData;
+use super::pathdata::{PathData, PathSegment};
+use super::Node;
 
 // TODO: implement Default for all
 
 
+/// Implements `PartialEq`, `Eq` and `Hash` using the type's `key` method.
+macro_rules! impl_structural_eq {
+    ($name:ident) => {
+        impl PartialEq for $name {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.key() == other.key()
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl Hash for $name {
+            #[inline]
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.key().hash(state)
+            }
+        }
+    };
+}
+
+/// Returns a float bit pattern that can be compared and hashed.
+///
+/// Both zeros are treated as the same value.
+#[inline]
+fn f64_bits(n: f64) -> u64 {
+    if n == 0.0 { 0 } else { n.to_bits() }
+}
+
+#[inline]
+fn transform_bits(ts: &Transform) -> [u64; 6] {
+    [f64_bits(ts.a), f64_bits(ts.b), f64_bits(ts.c), f64_bits(ts.d), f64_bits(ts.e), f64_bits(ts.f)]
+}
+
+#[inline]
+fn rect_bits(r: &Rect) -> [u64; 4] {
+    [f64_bits(r.x()), f64_bits(r.y()), f64_bits(r.width()), f64_bits(r.height())]
+}
+
+fn view_box_key(vb: &ViewBox) -> ([u64; 4], [u8; 3]) {
+    let aspect = [vb.aspect.defer as u8, vb.aspect.align as u8, vb.aspect.slice as u8];
+    (rect_bits(&vb.rect), aspect)
+}
+
+fn paint_key(paint: &Paint) -> (Option<[u8; 3]>, Option<&str>) {
+    match *paint {
+        Paint::Color(c) => (Some([c.red, c.green, c.blue]), None),
+        Paint::Link(ref id) => (None, Some(id.as_str())),
+    }
+}
+
+fn fill_key(fill: &Fill) -> impl Eq + Hash + '_ {
+    (paint_key(&fill.paint), f64_bits(fill.opacity.value()), fill.rule as u8)
+}
+
+fn stroke_key(stroke: &Stroke) -> impl Eq + Hash + '_ {
+    let dasharray = stroke.dasharray.as_ref().map(|list| {
+        list.iter().map(|n| f64_bits(*n)).collect::<Vec<_>>()
+    });
+
+    let nums = [
+        f64_bits(stroke.dashoffset as f64), f64_bits(stroke.miterlimit.value()),
+        f64_bits(stroke.opacity.value()), f64_bits(stroke.width.value()),
+    ];
+
+    (
+        paint_key(&stroke.paint), dasharray, nums,
+        [stroke.linecap as u8, stroke.linejoin as u8, stroke.non_scaling as u8],
+    )
+}
+
+fn path_data_key(data: &PathData) -> Vec<(u8, [u64; 6])> {
+    data.iter().map(|seg| match *seg {
+        PathSegment::MoveTo { x, y } => (0, [f64_bits(x), f64_bits(y), 0, 0, 0, 0]),
+        PathSegment::LineTo { x, y } => (1, [f64_bits(x), f64_bits(y), 0, 0, 0, 0]),
+        PathSegment::CurveTo { x1, y1, x2, y2, x, y } => {
+            (2, [f64_bits(x1), f64_bits(y1), f64_bits(x2), f64_bits(y2), f64_bits(x), f64_bits(y)])
+        }
+        PathSegment::ClosePath => (3, [0; 6]),
+    }).collect()
+}
+
+
 /// Node's kind.
 #[allow(missing_docs)]
 #[derive(Clone, Debug)]
@@ -69,11 +155,77 @@ impl NodeKind {
             NodeKind::Group(ref e) => e.transform,
         }
     }
+
+    /// Checks that two nodes are structurally equal, ignoring their children.
+    fn structural_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (NodeKind::LinearGradient(a), NodeKind::LinearGradient(b)) => a == b,
+            (NodeKind::RadialGradient(a), NodeKind::RadialGradient(b)) => a == b,
+            (NodeKind::Pattern(a), NodeKind::Pattern(b)) => a.key() == b.key(),
+            (NodeKind::Path(a), NodeKind::Path(b)) => a.key() == b.key(),
+            (NodeKind::Image(a), NodeKind::Image(b)) => a.key() == b.key(),
+            (NodeKind::Group(a), NodeKind::Group(b)) => a.key() == b.key(),
+            // Other nodes are unique inside a tree, so they are compared by ID.
+            _ => mem::discriminant(self) == mem::discriminant(other) && self.id() == other.id(),
+        }
+    }
+
+    /// Hashes a node structure, ignoring its children.
+    fn structural_hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match *self {
+            NodeKind::LinearGradient(ref e) => e.hash(state),
+            NodeKind::RadialGradient(ref e) => e.hash(state),
+            NodeKind::Pattern(ref e) => e.key().hash(state),
+            NodeKind::Path(ref e) => e.key().hash(state),
+            NodeKind::Image(ref e) => e.key().hash(state),
+            NodeKind::Group(ref e) => e.key().hash(state),
+            _ => self.id().hash(state),
+        }
+    }
+}
+
+
+/// A node with structural equality and hashing.
+///
+/// Unlike `NodeKind`, the whole subtree is compared. This is required for a `Pattern`,
+/// since its content is stored as child nodes. Element IDs are ignored, while links
+/// to other elements are compared by ID. `ClipPath`, `Mask` and `Filter` nodes
+/// are compared only by ID, so the nodes from different trees should not be mixed.
+#[derive(Clone, Debug)]
+pub struct StructuralNode(pub Node);
+
+impl PartialEq for StructuralNode {
+    fn eq(&self, other: &Self) -> bool {
+        fn eq_nodes(a: &Node, b: &Node) -> bool {
+            a.borrow().structural_eq(&b.borrow())
+                && a.children().count() == b.children().count()
+                && a.children().zip(b.children()).all(|(a, b)| eq_nodes(&a, &b))
+        }
+
+        eq_nodes(&self.0, &other.0)
+    }
+}
+
+impl Eq for StructuralNode {}
+
+impl Hash for StructuralNode {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        fn hash_node<H: Hasher>(node: &Node, state: &mut H) {
+            node.borrow().structural_hash(state);
+            node.children().count().hash(state);
+            for child in node.children() {
+                hash_node(&child, state);
+            }
+        }
+
+        hash_node(&self.0, state)
+    }
 }
 
 
 /// An SVG root element.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct Svg {
     /// Image size.
     ///
@@ -88,6 +240,14 @@ pub struct Svg {
     ///
     /// `viewBox` and `preserveAspectRatio` in SVG.
     pub view_box: ViewBox,
+
+    /// The raw XML of the root `metadata` element.
+    ///
+    /// The metadata is not processed in any way and is used only during the export,
+    /// where it's written element by element. Comments are not preserved.
+    /// Namespaces declared on the root `svg` element and used by the metadata
+    /// are copied to the `metadata` element.
+    pub metadata: Option<String>,
 }
 
 
@@ -113,6 +273,11 @@ pub struct Path {
     /// Stroke style.
     pub stroke: Option<Stroke>,
 
+    /// Fill and stroke paint order.
+    ///
+    /// `paint-order` in SVG.
+    pub paint_order: PaintOrder,
+
     /// Rendering mode.
     ///
     /// `shape-rendering` in SVG.
@@ -124,6 +289,18 @@ pub struct Path {
     pub data: Rc<PathData>,
 }
 
+impl Path {
+    fn key(&self) -> impl Eq + Hash + '_ {
+        (
+            transform_bits(&self.transform),
+            [self.visibility as u8, self.paint_order as u8, self.rendering_mode as u8],
+            self.fill.as_ref().map(fill_key),
+            self.stroke.as_ref().map(stroke_key),
+            path_data_key(&self.data),
+        )
+    }
+}
+
 impl Default for Path {
     fn default() -> Self {
         Path {
@@ -132,6 +309,7 @@ impl Default for Path {
             visibility: Visibility::Visible,
             fill: None,
             stroke: None,
+            paint_order: PaintOrder::default(),
             rendering_mode: ShapeRendering::default(),
             data: Rc::new(PathData::default()),
         }
@@ -175,6 +353,22 @@ pub struct Image {
     pub format: ImageFormat,
 }
 
+impl Image {
+    fn key(&self) -> impl Eq + Hash + '_ {
+        let data = match self.data {
+            ImageData::Path(ref path) => (Some(path.as_path()), None),
+            ImageData::Raw(ref data) => (None, Some(data.as_slice())),
+        };
+
+        (
+            transform_bits(&self.transform),
+            [self.visibility as u8, self.rendering_mode as u8, self.format as u8],
+            view_box_key(&self.view_box),
+            data,
+        )
+    }
+}
+
 
 /// A group container.
 ///
@@ -225,6 +419,18 @@ pub struct Group {
     pub enable_background: Option<EnableBackground>,
 }
 
+impl Group {
+    fn key(&self) -> impl Eq + Hash + '_ {
+        (
+            transform_bits(&self.transform),
+            f64_bits(self.opacity.value()),
+            [self.clip_path.as_deref(), self.mask.as_deref(), self.filter.as_deref()],
+            [self.filter_fill.as_ref().map(paint_key), self.filter_stroke.as_ref().map(paint_key)],
+            self.enable_background.map(|eb| eb.0.map(|r| rect_bits(&r))),
+        )
+    }
+}
+
 impl Default for Group {
     fn default() -> Self {
         Group {
@@ -243,6 +449,8 @@ impl Default for Group {
 
 
 /// A generic gradient.
+///
+/// Equality and hashing are structural. Floats are compared by their bit patterns.
 #[derive(Clone, Debug)]
 pub struct BaseGradient {
     /// Coordinate system units.
@@ -264,10 +472,21 @@ pub struct BaseGradient {
     pub stops: Vec<Stop>,
 }
 
+impl BaseGradient {
+    fn key(&self) -> impl Eq + Hash + '_ {
+        (self.units as u8, transform_bits(&self.transform), self.spread_method as u8, &self.stops)
+    }
+}
+
+impl_structural_eq!(BaseGradient);
+
 
 /// A linear gradient.
 ///
 /// `linearGradient` element in SVG.
+///
+/// Equality and hashing are structural and ignore the `id`,
+/// so gradients from different elements can be deduplicated.
 #[allow(missing_docs)]
 #[derive(Clone, Debug)]
 pub struct LinearGradient {
@@ -286,6 +505,14 @@ pub struct LinearGradient {
     pub base: BaseGradient,
 }
 
+impl LinearGradient {
+    fn key(&self) -> impl Eq + Hash + '_ {
+        ([f64_bits(self.x1), f64_bits(self.y1), f64_bits(self.x2), f64_bits(self.y2)], &self.base)
+    }
+}
+
+impl_structural_eq!(LinearGradient);
+
 impl Deref for LinearGradient {
     type Target = BaseGradient;
 
@@ -298,6 +525,8 @@ impl Deref for LinearGradient {
 /// A radial gradient.
 ///
 /// `radialGradient` element in SVG.
+///
+/// Equality and hashing are structural and ignore the `id`.
 #[allow(missing_docs)]
 #[derive(Clone, Debug)]
 pub struct RadialGradient {
@@ -317,6 +546,18 @@ pub struct RadialGradient {
     pub base: BaseGradient,
 }
 
+impl RadialGradient {
+    fn key(&self) -> impl Eq + Hash + '_ {
+        let nums = [
+            f64_bits(self.cx), f64_bits(self.cy), f64_bits(self.r.value()),
+            f64_bits(self.fx), f64_bits(self.fy),
+        ];
+        (nums, &self.base)
+    }
+}
+
+impl_structural_eq!(RadialGradient);
+
 impl Deref for RadialGradient {
     type Target = BaseGradient;
 
@@ -347,6 +588,15 @@ pub struct Stop {
     pub opacity: Opacity,
 }
 
+impl Stop {
+    fn key(&self) -> impl Eq + Hash {
+        let c = self.color;
+        (f64_bits(self.offset.value()), [c.red, c.green, c.blue], f64_bits(self.opacity.value()))
+    }
+}
+
+impl_structural_eq!(Stop);
+
 
 /// A clip-path element.
 ///
@@ -413,6 +663,13 @@ pub struct Mask {
     /// `x`, `y`, `width` and `height` in SVG.
     pub rect: Rect,
 
+    /// Mask type.
+    ///
+    /// Specifies whether the luminance or the alpha channel of the mask content should be used.
+    ///
+    /// `mask-type` in SVG.
+    pub kind: MaskType,
+
     /// Additional mask.
     ///
     /// `mask` in SVG.
@@ -423,6 +680,9 @@ pub struct Mask {
 /// A pattern element.
 ///
 /// `pattern` element in SVG.
+///
+/// Doesn't implement structural equality, because the pattern content is stored
+/// as node children. Use `StructuralNode` instead.
 #[derive(Clone, Debug)]
 pub struct Pattern {
     /// Element's ID.
@@ -456,6 +716,19 @@ pub struct Pattern {
     pub view_box: Option<ViewBox>,
 }
 
+impl Pattern {
+    /// The pattern content is stored as node children,
+    /// so it's compared only by `StructuralNode`.
+    fn key(&self) -> impl Eq + Hash + '_ {
+        (
+            [self.units as u8, self.content_units as u8],
+            transform_bits(&self.transform),
+            rect_bits(&self.rect),
+            self.view_box.as_ref().map(view_box_key),
+        )
+    }
+}
+
 
 /// A filter element.
 ///
@@ -535,6 +808,7 @@ pub enum FilterKind {
     FeMerge(FeMerge),
     FeMorphology(FeMorphology),
     FeOffset(FeOffset),
+    FePassThrough(FePassThrough),
     FeSpecularLighting(FeSpecularLighting),
     FeTile(FeTile),
     FeTurbulence(FeTurbulence),
@@ -557,6 +831,7 @@ impl FilterKind {
             FilterKind::FeMerge(ref fe) => fe.inputs.iter().any(|i| i == input),
             FilterKind::FeMorphology(ref fe) => fe.input == *input,
             FilterKind::FeOffset(ref fe) => fe.input == *input,
+            FilterKind::FePassThrough(ref fe) => fe.input == *input,
             FilterKind::FeSpecularLighting(ref fe) => fe.input == *input,
             FilterKind::FeTile(ref fe) => fe.input == *input,
             FilterKind::FeTurbulence(_) => false,
@@ -920,6 +1195,19 @@ pub struct FeOffset {
 }
 
 
+/// A pass through filter primitive.
+///
+/// Replaces an unsupported filter primitive, so its `result` can still be referenced.
+/// Has no SVG equivalent and is written as an `feMerge` with a single input during export.
+#[derive(Clone, Debug)]
+pub struct FePassThrough {
+    /// Identifies input for the given filter primitive.
+    ///
+    /// `in` in the SVG.
+    pub input: FilterInput,
+}
+
+
 /// A specular lighting filter primitive.
 ///
 /// `feSpecularLighting` element in the SVG.
@@ -1096,3 +1384,109 @@ pub struct FeSpotLight {
     /// `limitingConeAngle` in the SVG.
     pub limiting_cone_angle: Option<f64>,
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::NodeExt;
+    use std::collections::hash_map::DefaultHasher;
+
+    fn hash<T: Hash>(v: &T) -> u64 {
+        let mut state = DefaultHasher::new();
+        v.hash(&mut state);
+        state.finish()
+    }
+
+    fn gradient(id: &str, x2: f64, stop_color: Color) -> LinearGradient {
+        LinearGradient {
+            id: id.to_string(),
+            x1: 0.0,
+            y1: 0.0,
+            x2,
+            y2: 0.0,
+            base: BaseGradient {
+                units: Units::ObjectBoundingBox,
+                transform: Transform::default(),
+                spread_method: SpreadMethod::Pad,
+                stops: vec![
+                    Stop { offset: 0.0.into(), color: Color::black(), opacity: 1.0.into() },
+                    Stop { offset: 1.0.into(), color: stop_color, opacity: 1.0.into() },
+                ],
+            },
+        }
+    }
+
+    #[test]
+    fn structural_eq_1() {
+        let lg1 = gradient("lg1", 1.0, Color::white());
+        let lg2 = gradient("lg2", 1.0, Color::white());
+        assert!(lg1 == lg2);
+        assert_eq!(hash(&lg1), hash(&lg2));
+    }
+
+    #[test]
+    fn structural_eq_2() {
+        let lg1 = gradient("lg1", 1.0, Color::white());
+        let lg2 = gradient("lg1", 0.5, Color::white());
+        assert!(lg1 != lg2);
+        assert_ne!(hash(&lg1), hash(&lg2));
+
+        let lg3 = gradient("lg1", 1.0, Color::new(255, 0, 0));
+        assert!(lg1 != lg3);
+        assert_ne!(hash(&lg1), hash(&lg3));
+    }
+
+    #[test]
+    fn structural_eq_3() {
+        // Both zeros are the same value.
+        let lg1 = gradient("lg1", 0.0, Color::white());
+        let lg2 = gradient("lg2", -0.0, Color::white());
+        assert!(lg1 == lg2);
+        assert_eq!(hash(&lg1), hash(&lg2));
+    }
+
+    fn pattern(id: &str, content_x: f64) -> StructuralNode {
+        let mut node = Node::new(NodeKind::Pattern(Pattern {
+            id: id.to_string(),
+            units: Units::UserSpaceOnUse,
+            content_units: Units::UserSpaceOnUse,
+            transform: Transform::default(),
+            rect: Rect::new(0.0, 0.0, 10.0, 10.0).unwrap(),
+            view_box: None,
+        }));
+
+        let mut data = PathData::new();
+        data.push_rect(Rect::new(content_x, 0.0, 5.0, 5.0).unwrap());
+        node.append_kind(NodeKind::Path(Path {
+            id: format!("{}-rect", id),
+            fill: Some(Fill::default()),
+            data: Rc::new(data),
+            ..Path::default()
+        }));
+
+        StructuralNode(node)
+    }
+
+    #[test]
+    fn structural_eq_pattern_1() {
+        let patt1 = pattern("patt1", 0.0);
+        let patt2 = pattern("patt2", 0.0);
+        assert!(patt1 == patt2);
+        assert_eq!(hash(&patt1), hash(&patt2));
+    }
+
+    #[test]
+    fn structural_eq_pattern_2() {
+        // Same attributes, but a different content.
+        let patt1 = pattern("patt1", 0.0);
+        let patt2 = pattern("patt1", 5.0);
+        assert!(patt1 != patt2);
+        assert_ne!(hash(&patt1), hash(&patt2));
+
+        let patt3 = pattern("patt1", 0.0);
+        patt3.0.first_child().unwrap().detach();
+        assert!(patt1 != patt3);
+        assert_ne!(hash(&patt1), hash(&patt3));
+    }
+}
@@ -437,8 +437,11 @@ fn calc_bbox_with_transform(
 
     // TODO: find a better way
     // It's an approximation, but it's better than nothing.
-    if let Some(ref stroke) = stroke {
-        let w = stroke.width.value() / 2.0;
+    if let Some(stroke) = stroke {
+        // `ts` is treated as a device transform for a non-scaling stroke.
+        let stroke = stroke.resolve_non_scaling(ts);
+        let (sx, sy) = ts.get_scale();
+        let w = stroke.width.value() * (sx * sy).sqrt() / 2.0;
         minx -= w;
         miny -= w;
         maxx += w;
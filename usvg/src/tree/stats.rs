@@ -0,0 +1,118 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+
+
+/// A `Tree` content summary.
+///
+/// Can be used to estimate the rendering cost before rendering.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct Stats {
+    /// The number of paths, including the one inside clip paths, masks and patterns.
+    ///
+    /// Text is converted into paths, so it's included too.
+    pub paths: usize,
+
+    /// The total number of path segments.
+    pub path_segments: usize,
+
+    /// The number of images.
+    pub images: usize,
+
+    /// The total size of the decoded raster images in bytes.
+    ///
+    /// Assumes 4 bytes per pixel. Only the image headers are read, so it's cheap.
+    /// SVG images and images that cannot be read are not included.
+    pub images_decoded_size: usize,
+
+    /// The number of linear and radial gradients.
+    pub gradients: usize,
+
+    /// The number of patterns.
+    pub patterns: usize,
+
+    /// The number of clip paths.
+    pub clip_paths: usize,
+
+    /// The maximum nesting depth of the rendered elements.
+    ///
+    /// The root element children have a depth of 1. Elements inside `defs` are ignored.
+    pub max_depth: usize,
+}
+
+pub fn calc(tree: &Tree) -> Stats {
+    let mut stats = Stats::default();
+
+    for node in tree.root().descendants() {
+        match *node.borrow() {
+            NodeKind::Path(ref path) => {
+                stats.paths += 1;
+                stats.path_segments += path.data.len();
+            }
+            NodeKind::Image(ref img) => {
+                stats.images += 1;
+                stats.images_decoded_size += image_decoded_size(&img.data, img.format).unwrap_or(0);
+            }
+            NodeKind::LinearGradient(_) | NodeKind::RadialGradient(_) => stats.gradients += 1,
+            NodeKind::Pattern(_) => stats.patterns += 1,
+            NodeKind::ClipPath(_) => stats.clip_paths += 1,
+            _ => {}
+        }
+
+        if !tree.is_in_defs(&node) {
+            // Skip the node itself.
+            let depth = node.ancestors().count() - 1;
+            stats.max_depth = stats.max_depth.max(depth);
+        }
+    }
+
+    stats
+}
+
+fn image_decoded_size(data: &ImageData, format: ImageFormat) -> Option<usize> {
+    let (width, height) = match data {
+        ImageData::Path(ref path) => image_size(&std::fs::read(path).ok()?, format)?,
+        ImageData::Raw(ref data) => image_size(data, format)?,
+    };
+
+    Some(width as usize * height as usize * 4)
+}
+
+/// Reads an image size from a PNG or JPEG header.
+fn image_size(data: &[u8], format: ImageFormat) -> Option<(u16, u16)> {
+    let read_u16 = |i: usize| -> Option<u16> {
+        Some(u16::from_be_bytes([*data.get(i)?, *data.get(i + 1)?]))
+    };
+
+    match format {
+        ImageFormat::PNG => {
+            // The `IHDR` chunk is always the first one.
+            // Sizes are stored as `u32`, but larger values cannot be decoded anyway.
+            if read_u16(16)? != 0 || read_u16(20)? != 0 {
+                return None;
+            }
+
+            Some((read_u16(18)?, read_u16(22)?))
+        }
+        ImageFormat::JPEG => {
+            // Skip segments until the start of frame one.
+            let mut i = 2;
+            loop {
+                if *data.get(i)? != 0xFF {
+                    return None;
+                }
+
+                let marker = *data.get(i + 1)?;
+                match marker {
+                    0xC0..=0xCF if marker != 0xC4 && marker != 0xC8 && marker != 0xCC => {
+                        return Some((read_u16(i + 7)?, read_u16(i + 5)?));
+                    }
+                    _ => i += 2 + read_u16(i + 2)? as usize,
+                }
+            }
+        }
+        ImageFormat::SVG => None,
+    }
+}
@@ -0,0 +1,273 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A streaming version of `xmlwriter::XmlWriter`.
+//!
+//! `xmlwriter` can write only into an internal buffer, so we have to reimplement it.
+//! The output is identical to the `xmlwriter` one.
+
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+
+use crate::{XmlIndent, XmlOptions};
+
+/// The buffer size after which the data will be written into the sink.
+const FLUSH_THRESHOLD: usize = 8 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum State {
+    Empty,
+    Document,
+    Attributes,
+}
+
+struct DepthData {
+    /// An element name start in `XmlWriter::names`.
+    name_start: usize,
+    has_children: bool,
+}
+
+pub struct XmlWriter<'a> {
+    sink: &'a mut dyn Write,
+    /// The current element data. Written into the `sink` between elements.
+    buf: Vec<u8>,
+    /// Names of all open elements.
+    names: String,
+    /// The first sink error. All writing will be skipped after it.
+    error: Option<io::Error>,
+    state: State,
+    preserve_whitespaces: bool,
+    depth_stack: Vec<DepthData>,
+    opt: XmlOptions,
+}
+
+impl<'a> XmlWriter<'a> {
+    pub fn new(sink: &'a mut dyn Write, opt: XmlOptions) -> Self {
+        XmlWriter {
+            sink,
+            buf: Vec::new(),
+            names: String::new(),
+            error: None,
+            state: State::Empty,
+            preserve_whitespaces: false,
+            depth_stack: Vec::new(),
+            opt,
+        }
+    }
+
+    pub fn start_element(&mut self, name: &str) {
+        if self.state == State::Attributes {
+            self.write_open_element();
+        }
+
+        if self.state != State::Empty {
+            self.write_new_line();
+        }
+
+        self.write_node_indent();
+
+        self.buf.push(b'<');
+        self.buf.extend_from_slice(name.as_bytes());
+
+        self.depth_stack.push(DepthData {
+            name_start: self.names.len(),
+            has_children: false,
+        });
+        self.names.push_str(name);
+
+        self.state = State::Attributes;
+    }
+
+    pub fn write_attribute<V: Display + ?Sized>(&mut self, name: &str, value: &V) {
+        self.write_attribute_fmt(name, format_args!("{}", value));
+    }
+
+    pub fn write_attribute_fmt(&mut self, name: &str, fmt: fmt::Arguments) {
+        self.write_attribute_raw(name, |buf| buf.write_fmt(fmt).unwrap());
+    }
+
+    pub fn write_attribute_raw<F>(&mut self, name: &str, f: F)
+        where F: FnOnce(&mut Vec<u8>)
+    {
+        self.write_attribute_prefix(name);
+        let start = self.buf.len();
+        f(&mut self.buf);
+        self.escape_attribute_value(start);
+        self.write_quote();
+    }
+
+    /// Writes an attribute value directly into the sink.
+    ///
+    /// Unlike `write_attribute_raw`, the value is not buffered and not escaped,
+    /// so it must not contain quotes.
+    pub fn write_attribute_stream<F>(&mut self, name: &str, f: F)
+        where F: FnOnce(&mut dyn Write) -> io::Result<()>
+    {
+        self.write_attribute_prefix(name);
+        self.flush_buf();
+        if self.error.is_none() {
+            if let Err(e) = f(self.sink) {
+                self.error = Some(e);
+            }
+        }
+        self.write_quote();
+    }
+
+    /// Disables indentation and new lines, which is required for text nodes.
+    pub fn set_preserve_whitespaces(&mut self, preserve: bool) {
+        self.preserve_whitespaces = preserve;
+    }
+
+    pub fn write_text(&mut self, text: &str) {
+        if self.depth_stack.is_empty() {
+            panic!("must be called after start_element()");
+        }
+
+        if self.state == State::Attributes {
+            self.write_open_element();
+        }
+
+        self.write_new_line();
+        self.write_node_indent();
+
+        for c in text.bytes() {
+            match c {
+                b'&' => self.buf.extend_from_slice(b"&amp;"),
+                b'<' => self.buf.extend_from_slice(b"&lt;"),
+                b'>' => self.buf.extend_from_slice(b"&gt;"),
+                _ => self.buf.push(c),
+            }
+        }
+
+        self.state = State::Document;
+    }
+
+    pub fn end_element(&mut self) {
+        if let Some(depth) = self.depth_stack.pop() {
+            if depth.has_children {
+                self.write_new_line();
+                self.write_node_indent();
+
+                self.buf.extend_from_slice(b"</");
+                self.buf.extend_from_slice(&self.names.as_bytes()[depth.name_start..]);
+                self.buf.push(b'>');
+            } else {
+                self.buf.extend_from_slice(b"/>");
+            }
+
+            self.names.truncate(depth.name_start);
+        }
+
+        self.state = State::Document;
+
+        if self.buf.len() >= FLUSH_THRESHOLD {
+            self.flush_buf();
+        }
+    }
+
+    pub fn end_document(mut self) -> io::Result<()> {
+        while !self.depth_stack.is_empty() {
+            self.end_element();
+        }
+
+        self.write_new_line();
+        self.flush_buf();
+
+        match self.error {
+            Some(e) => Err(e),
+            None => self.sink.flush(),
+        }
+    }
+
+    fn flush_buf(&mut self) {
+        if self.error.is_none() {
+            if let Err(e) = self.sink.write_all(&self.buf) {
+                self.error = Some(e);
+            }
+        }
+
+        self.buf.clear();
+    }
+
+    fn write_attribute_prefix(&mut self, name: &str) {
+        if self.state != State::Attributes {
+            panic!("must be called after start_element()");
+        }
+
+        if self.opt.attributes_indent == XmlIndent::None {
+            self.buf.push(b' ');
+        } else {
+            self.buf.push(b'\n');
+
+            let depth = self.depth_stack.len();
+            if depth > 0 {
+                self.write_indent(depth - 1, self.opt.indent);
+            }
+
+            self.write_indent(1, self.opt.attributes_indent);
+        }
+
+        self.buf.extend_from_slice(name.as_bytes());
+        self.buf.push(b'=');
+        self.write_quote();
+    }
+
+    fn escape_attribute_value(&mut self, mut start: usize) {
+        let quote = self.quote_char();
+        while let Some(idx) = self.buf[start..].iter().position(|c| *c == quote || *c == b'&' || *c == b'<') {
+            let i = start + idx;
+            let escaped: &[u8] = match self.buf[i] {
+                b'&' => b"&amp;",
+                b'<' => b"&lt;",
+                _ if self.opt.use_single_quote => b"&apos;",
+                _ => b"&quot;",
+            };
+            self.buf.splice(i..i+1, escaped.iter().cloned());
+            start = i + escaped.len();
+        }
+    }
+
+    fn quote_char(&self) -> u8 {
+        if self.opt.use_single_quote { b'\'' } else { b'"' }
+    }
+
+    fn write_quote(&mut self) {
+        let c = self.quote_char();
+        self.buf.push(c);
+    }
+
+    fn write_open_element(&mut self) {
+        if let Some(depth) = self.depth_stack.last_mut() {
+            depth.has_children = true;
+            self.buf.push(b'>');
+            self.state = State::Document;
+        }
+    }
+
+    fn write_node_indent(&mut self) {
+        self.write_indent(self.depth_stack.len(), self.opt.indent);
+    }
+
+    fn write_indent(&mut self, depth: usize, indent: XmlIndent) {
+        if self.preserve_whitespaces {
+            return;
+        }
+
+        for _ in 0..depth {
+            match indent {
+                XmlIndent::None => {}
+                XmlIndent::Spaces(n) => {
+                    self.buf.extend(std::iter::repeat_n(b' ', n as usize));
+                }
+                XmlIndent::Tabs => self.buf.push(b'\t'),
+            }
+        }
+    }
+
+    fn write_new_line(&mut self) {
+        if self.opt.indent != XmlIndent::None && !self.preserve_whitespaces {
+            self.buf.push(b'\n');
+        }
+    }
+}
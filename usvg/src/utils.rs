@@ -4,6 +4,8 @@
 
 //! Some useful utilities.
 
+use svgtypes::FuzzyZero;
+
 use crate::{tree, geom::*};
 
 
@@ -15,18 +17,10 @@ pub fn view_box_to_transform(
 ) -> tree::Transform {
     let vr = view_box;
 
-    let sx = img_size.width() / vr.width();
-    let sy = img_size.height() / vr.height();
-
     let (sx, sy) = if aspect.align == tree::Align::None {
-        (sx, sy)
+        (img_size.width() / vr.width(), img_size.height() / vr.height())
     } else {
-        let s = if aspect.slice {
-            if sx < sy { sy } else { sx }
-        } else {
-            if sx > sy { sy } else { sx }
-        };
-
+        let s = vr.size().fit_scale(img_size, aspect.slice);
         (s, s)
     };
 
@@ -39,6 +33,22 @@ pub fn view_box_to_transform(
     tree::Transform::new(sx, 0.0, 0.0, sy, tx, ty)
 }
 
+/// Converts `viewBox` to `Transform`.
+///
+/// Unlike `view_box_to_transform`, returns `None` when the resulting transform
+/// is not finite or not invertible, which can happen with extreme sizes.
+pub fn checked_view_box_to_transform(
+    view_box: Rect,
+    aspect: tree::AspectRatio,
+    img_size: Size,
+) -> Option<tree::Transform> {
+    let ts = view_box_to_transform(view_box, aspect, img_size);
+    let is_valid = [ts.a, ts.d, ts.e, ts.f].iter().all(|n| n.is_finite())
+        && !ts.a.is_fuzzy_zero() && !ts.d.is_fuzzy_zero();
+
+    if is_valid { Some(ts) } else { None }
+}
+
 /// Returns object aligned position.
 pub fn aligned_pos(
     align: tree::Align,
@@ -65,3 +75,61 @@ pub(crate) fn file_extension(path: &std::path::Path) -> Option<&str> {
         None
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FuzzyEq;
+
+    fn view_box_offset(align: tree::Align, slice: bool) -> (f64, f64) {
+        let aspect = tree::AspectRatio { defer: false, align, slice };
+        let view_box = Rect::new(0.0, 0.0, 100.0, 50.0).unwrap();
+        let ts = view_box_to_transform(view_box, aspect, Size::new(200.0, 200.0).unwrap());
+        (ts.e, ts.f)
+    }
+
+    #[test]
+    fn view_box_to_transform_meet() {
+        use tree::Align::*;
+
+        // Scaled by 2 to 200x100, so only the vertical alignment matters.
+        for &(align, y) in &[
+            (XMinYMin, 0.0), (XMidYMin, 0.0), (XMaxYMin, 0.0),
+            (XMinYMid, 50.0), (XMidYMid, 50.0), (XMaxYMid, 50.0),
+            (XMinYMax, 100.0), (XMidYMax, 100.0), (XMaxYMax, 100.0),
+        ] {
+            let (tx, ty) = view_box_offset(align, false);
+            assert!(tx.fuzzy_eq(&0.0) && ty.fuzzy_eq(&y), "{:?}", align);
+        }
+    }
+
+    #[test]
+    fn view_box_to_transform_slice() {
+        use tree::Align::*;
+
+        // Scaled by 4 to 400x200, so only the horizontal alignment matters.
+        for &(align, x) in &[
+            (XMinYMin, 0.0), (XMidYMin, -100.0), (XMaxYMin, -200.0),
+            (XMinYMid, 0.0), (XMidYMid, -100.0), (XMaxYMid, -200.0),
+            (XMinYMax, 0.0), (XMidYMax, -100.0), (XMaxYMax, -200.0),
+        ] {
+            let (tx, ty) = view_box_offset(align, true);
+            assert!(tx.fuzzy_eq(&x) && ty.fuzzy_eq(&0.0), "{:?}", align);
+        }
+    }
+
+    #[test]
+    fn checked_view_box_to_transform_1() {
+        let aspect = tree::AspectRatio::default();
+        let size = Size::new(200.0, 200.0).unwrap();
+
+        let view_box = Rect::new(0.0, 0.0, 100.0, 50.0).unwrap();
+        let ts = checked_view_box_to_transform(view_box, aspect, size).unwrap();
+        assert!(ts.a.fuzzy_eq(&2.0) && ts.d.fuzzy_eq(&2.0));
+
+        // The scale is infinite.
+        let view_box = Rect::new(0.0, 0.0, 1e-310, 1e-310).unwrap();
+        assert!(checked_view_box_to_transform(view_box, aspect, size).is_none());
+    }
+}
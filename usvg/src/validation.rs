@@ -0,0 +1,205 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt;
+
+use crate::svgtree::{self, AId, AttributeValue, EId};
+use crate::{Error, Options};
+
+
+/// A validation issue severity.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Severity {
+    /// The document will be rendered, but probably not as intended.
+    Warning,
+    /// A part of the document will not be rendered.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+
+/// A structural problem found by `validate`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ValidationIssue {
+    /// Issue severity.
+    pub severity: Severity,
+
+    /// An ID of the element with the issue.
+    ///
+    /// Can be empty.
+    pub element_id: String,
+
+    /// Issue description.
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.element_id.is_empty() {
+            write!(f, "{}: {}", self.severity, self.message)
+        } else {
+            write!(f, "{}: '{}': {}", self.severity, self.element_id, self.message)
+        }
+    }
+}
+
+
+/// Checks an SVG string for structural problems.
+///
+/// Reports links to non-existing elements, recursive links,
+/// invalid shape sizes and unsupported elements.
+///
+/// Unlike the warnings handler, which receives messages during the conversion,
+/// the document is only parsed here and the result doesn't depend on rendering.
+///
+/// # Errors
+///
+/// Returns `Error::ParsingFailed` when the document cannot be parsed at all.
+pub fn validate(text: &str, opt: &Options) -> Result<Vec<ValidationIssue>, Error> {
+    let mut issues = Vec::new();
+
+    let xml = roxmltree::Document::parse(text).map_err(Error::ParsingFailed)?;
+    check_xml(&xml, opt, &mut issues);
+
+    let doc = svgtree::Document::parse(text, opt).map_err(Error::ParsingFailed)?;
+    check_links(&doc, &mut issues);
+    check_shapes(&doc, &mut issues);
+
+    for &(node_id, aid) in doc.recursive_links() {
+        issues.push(ValidationIssue {
+            severity: Severity::Error,
+            element_id: doc.get(node_id).element_id().to_string(),
+            message: format!("recursive '{}' link", aid),
+        });
+    }
+
+    Ok(issues)
+}
+
+/// Checks elements that are resolved or skipped during parsing.
+fn check_xml(xml: &roxmltree::Document, opt: &Options, issues: &mut Vec<ValidationIssue>) {
+    // Elements that do not affect rendering.
+    const IGNORED: &[&str] = &[
+        "animate", "animateTransform", "desc", "metadata", "script", "set", "title",
+    ];
+
+    for node in xml.descendants().filter(|n| n.is_element()) {
+        if node.tag_name().namespace() != Some(svgtree::SVG_NS) {
+            continue;
+        }
+
+        // The `metadata` content is stored as is.
+        if node.ancestors().skip(1).any(|n| n.has_tag_name((svgtree::SVG_NS, "metadata"))) {
+            continue;
+        }
+
+        let name = node.tag_name().name();
+        let element_id = node.attribute("id").unwrap_or_default().to_string();
+        match EId::from_str(name) {
+            Some(EId::ForeignObject) if !opt.foreign_object_placeholder => {
+                issues.push(ValidationIssue {
+                    severity: Severity::Warning,
+                    element_id,
+                    message: "'foreignObject' is not supported and will be skipped".to_string(),
+                });
+            }
+            Some(EId::Use) => {
+                let link = node.attribute(("http://www.w3.org/1999/xlink", "href"))
+                    .or_else(|| node.attribute("href"))
+                    .and_then(|v| svgtypes::Stream::from(v).parse_iri().ok());
+
+                let is_valid = match link {
+                    Some(link) => xml.descendants().any(|n| n.attribute("id") == Some(link)),
+                    None => false,
+                };
+
+                if !is_valid {
+                    issues.push(ValidationIssue {
+                        severity: Severity::Error,
+                        element_id,
+                        message: "'use' references a non-existing element".to_string(),
+                    });
+                }
+            }
+            Some(_) => {}
+            None if IGNORED.contains(&name) => {}
+            None => {
+                issues.push(ValidationIssue {
+                    severity: Severity::Warning,
+                    element_id,
+                    message: format!("'{}' is not supported and will be skipped", name),
+                });
+            }
+        }
+    }
+}
+
+fn check_links(doc: &svgtree::Document, issues: &mut Vec<ValidationIssue>) {
+    for node in doc.descendants() {
+        for attr in node.attributes() {
+            let (link, has_fallback) = match attr.value {
+                AttributeValue::Link(ref link) => (link, false),
+                AttributeValue::Paint(ref link, ref fallback) => (link, fallback.is_some()),
+                _ => continue,
+            };
+
+            // Already checked by `check_xml`.
+            if node.has_tag_name(EId::Use) && attr.name == AId::Href {
+                continue;
+            }
+
+            if doc.element_by_id(link).is_some() {
+                continue;
+            }
+
+            let severity = match attr.name {
+                // An element with an invalid filter is not rendered.
+                AId::Filter => Severity::Error,
+                _ => Severity::Warning,
+            };
+
+            let mut message = format!("'{}' references a non-existing element '#{}'", attr.name, link);
+            if has_fallback {
+                message.push_str(", the fallback will be used");
+            }
+
+            issues.push(ValidationIssue {
+                severity,
+                element_id: node.element_id().to_string(),
+                message,
+            });
+        }
+    }
+}
+
+fn check_shapes(doc: &svgtree::Document, issues: &mut Vec<ValidationIssue>) {
+    for node in doc.descendants() {
+        let aids: &[AId] = match node.tag_name() {
+            Some(EId::Rect) | Some(EId::Image) => &[AId::Width, AId::Height, AId::Rx, AId::Ry],
+            Some(EId::Circle) => &[AId::R],
+            Some(EId::Ellipse) => &[AId::Rx, AId::Ry],
+            _ => continue,
+        };
+
+        for aid in aids {
+            if let Some(length) = node.attribute::<svgtypes::Length>(*aid) {
+                if length.num < 0.0 {
+                    issues.push(ValidationIssue {
+                        severity: Severity::Error,
+                        element_id: node.element_id().to_string(),
+                        message: format!("'{}' cannot be negative", aid),
+                    });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::Arc;
+
+
+thread_local! {
+    static HANDLER: RefCell<Option<WarningHandler>> = const { RefCell::new(None) };
+}
+
+
+/// A warnings handler.
+///
+/// Receives all warnings emitted during the SVG processing and rendering
+/// instead of the `log` crate.
+#[derive(Clone)]
+pub struct WarningHandler(Arc<dyn Fn(&str) + Send + Sync>);
+
+impl WarningHandler {
+    /// Creates a new handler from a callback.
+    pub fn new<F: Fn(&str) + Send + Sync + 'static>(f: F) -> Self {
+        WarningHandler(Arc::new(f))
+    }
+}
+
+impl fmt::Debug for WarningHandler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WarningHandler(..)")
+    }
+}
+
+
+/// A guard that restores the previous warnings handler on drop.
+///
+/// Created by `set_warning_handler`.
+#[must_use]
+#[derive(Debug)]
+pub struct WarningHandlerGuard {
+    prev: Option<Option<WarningHandler>>,
+}
+
+impl Drop for WarningHandlerGuard {
+    fn drop(&mut self) {
+        if let Some(prev) = self.prev.take() {
+            HANDLER.with(|h| *h.borrow_mut() = prev);
+        }
+    }
+}
+
+/// Sets the warnings handler for the current thread until the returned guard is dropped.
+///
+/// Does nothing when `handler` is `None`, so an already set handler will be used.
+pub fn set_warning_handler(handler: Option<&WarningHandler>) -> WarningHandlerGuard {
+    let prev = handler.map(|handler| {
+        HANDLER.with(|h| h.borrow_mut().replace(handler.clone()))
+    });
+
+    WarningHandlerGuard { prev }
+}
+
+/// Emits a warning.
+///
+/// The warning is passed to the current thread's warnings handler
+/// or to the `log` crate when no handler is set.
+pub fn emit_warning(args: fmt::Arguments) {
+    let handler = HANDLER.with(|h| h.borrow().clone());
+    match handler {
+        Some(handler) => (handler.0)(&args.to_string()),
+        None => log::warn!("{}", args),
+    }
+}
@@ -10,15 +10,28 @@ impl<'a> fmt::Debug for MStr<'a> {
     }
 }
 
+/// Returns the default options that use only the bundled fonts.
+fn test_options() -> usvg::Options {
+    let opt = usvg::Options::default();
+    opt.font_db.load_fonts_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../testing-tools/fonts"));
+    opt
+}
+
 macro_rules! test {
     ($name:ident) => {
+        test!($name, |_| {});
+    };
+    ($name:ident, $setup:expr) => {
         #[test]
         fn $name() {
             let name = stringify!($name).replace("_", "-");
             let in_str = std::fs::read_to_string(format!("tests/files/{}-in.svg", name)).unwrap();
             let out_str = std::fs::read_to_string(format!("tests/files/{}-out.svg", name)).unwrap();
 
-            let tree = usvg::Tree::from_str(&in_str, &usvg::Options::default()).unwrap();
+            let mut re_opt = test_options();
+            let setup: fn(&mut usvg::Options) = $setup;
+            setup(&mut re_opt);
+            let tree = usvg::Tree::from_str(&in_str, &re_opt).unwrap();
 
             let xml_opt = usvg::XmlOptions {
                 use_single_quote: false,
@@ -41,7 +54,7 @@ macro_rules! test_preserve {
 
             let re_opt = usvg::Options {
                 keep_named_groups: true,
-                .. usvg::Options::default()
+                .. test_options()
             };
             let tree = usvg::Tree::from_str(&in_str, &re_opt).unwrap();
 
@@ -57,24 +70,72 @@ macro_rules! test_preserve {
 }
 
 test!(minimal);
+test!(color_css4_keyword);
 test!(groups);
 test!(clippath_with_invalid_child);
 test!(clippath_with_invalid_children);
+test!(clippath_with_clippath);
 test!(group_clippath);
+test!(clip_path_basic_shape);
+test!(func_iri_with_quotes);
 test!(ignore_groups_with_id);
+test!(image_with_transform);
 test!(pattern_with_invalid_child);
 test!(pattern_without_children);
+test!(pattern_with_zero_size);
 test!(simplify_paths);
+test!(rendering_mode_from_style);
 test!(group_with_default_opacity);
 test!(group_with_an_invalid_child);
 test!(nested_group_with_an_invalid_child);
 test!(simple_switch);
 test!(switch_with_opacity);
+test!(switch_with_required_features);
+test!(visibility_on_child);
+test!(marker_on_path_without_paint);
+test!(marker_on_polyline);
+test!(marker_with_auto_start_reverse);
+test!(marker_with_context_paint);
+test!(marker_with_context_stroke);
+test!(marker_with_paint_order);
+test!(marker_with_hidden_overflow);
+test!(nested_svg_with_clip);
+test!(nested_svg_with_clip_and_visible_overflow);
+test!(defs_order);
+test!(display_none_on_elements);
+test!(style_element);
+test!(paint_with_invalid_link);
+test!(gradient_with_degenerate_stops);
+test!(gradient_forward_href);
+test!(stop_color_default);
+test!(stop_color_inherit_with_href);
+test!(stops_with_out_of_range_offsets);
+test!(stops_with_unordered_offsets);
+test!(stroke_dasharray_with_units);
+test!(stroke_width_units);
+test!(non_scaling_stroke_with_markers);
+test!(paint_order);
+test!(use_size_on_symbol);
+test!(use_symbol_with_slice);
+test!(use_with_inherited_paint);
+test!(use_size_on_rect);
+test!(use_size_on_svg);
+test!(use_gradient_filled_path);
+test!(metadata);
+test!(fe_gaussian_blur_with_per_axis_std_dev);
 test!(fe_image_duplicates);
 test!(fe_image_with_invalid_link);
 test!(fe_diffuse_lighting_without_light_source);
 test!(fe_specular_lighting_without_light_source);
 test!(fe_specular_lighting_with_invalid_specular_exponent);
+test!(filter_with_unsupported_primitive);
+test!(filter_tile_with_flood_subregion);
+test!(filter_convolve_matrix_sharpen);
+test!(filter_color_interpolation_override);
+test!(text_with_text_length);
+test!(rgba_color);
+test!(rgba_color_inherited_alpha);
+test!(rgba_color_inherited_opacity);
 // test!(fill_rule_on_text); // `fill-rule` cannot be set on `text`
 // test!(marker_with_visible_overflow); // Marker resolving should not produce a group.
 
@@ -90,6 +151,11 @@ test_preserve!(ignore_empty_groups_with_id);
 test_preserve!(keep_groups_with_id);
 
 
+// Dash offset, color and transform interpolation at the middle of the animation.
+// `set` is not active yet.
+test!(animation_time, |opt| opt.animation_time = Some(0.5));
+
+
 macro_rules! test_size {
     ($name:ident, $input:expr, $expected:expr) => {
         #[test]
@@ -130,3 +196,1098 @@ test_size_err!(size_detection_err_1,
 
 test_size_err!(size_detection_err_2,
     "<svg width='0' height='0' viewBox='0 0 10 20' xmlns='http://www.w3.org/2000/svg'>");
+
+
+fn text_width(kerning: &str) -> f64 {
+    use usvg::NodeExt;
+
+    let svg = format!("
+    <svg viewBox='0 0 200 100' xmlns='http://www.w3.org/2000/svg'>
+        <text x='10' y='50' font-family='DejaVu Sans' font-size='48'
+              letter-spacing='2' kerning='{}'>AVAV</text>
+    </svg>
+    ", kerning);
+
+    let tree = usvg::Tree::from_str(&svg, &test_options()).unwrap();
+    tree.root().calculate_bbox().unwrap().width()
+}
+
+#[test]
+fn text_kerning() {
+    let auto = text_width("auto");
+    let disabled = text_width("0");
+    // Letter spacing is still applied, but `AV` pairs are no longer kerned.
+    assert!(disabled > auto);
+    // An explicit length is added to the letter spacing.
+    assert!((text_width("5") - disabled - 15.0).abs() < 0.001);
+}
+
+#[test]
+fn text_paint_order() {
+    let svg = "
+    <svg viewBox='0 0 200 100' xmlns='http://www.w3.org/2000/svg'>
+        <text x='10' y='50' font-family='DejaVu Sans' font-size='48' text-decoration='underline'
+              fill='green' stroke='black' stroke-width='4' paint-order='stroke'>Text</text>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &test_options()).unwrap();
+    let mut count = 0;
+    for node in tree.root().descendants() {
+        if let usvg::NodeKind::Path(ref path) = *node.borrow() {
+            // Both glyphs and decorations should use the text paint order.
+            assert_eq!(path.paint_order, usvg::PaintOrder::StrokeAndFill);
+            count += 1;
+        }
+    }
+
+    assert!(count > 1);
+}
+
+#[test]
+fn warning_handler() {
+    use std::sync::{Arc, Mutex};
+
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1'>
+            <rect width='10' height='10'/>
+        </filter>
+        <rect width='100' height='100' filter='url(#filter1)'/>
+    </svg>
+    ";
+
+    let warnings = Arc::new(Mutex::new(Vec::new()));
+    let warnings2 = warnings.clone();
+    let opt = usvg::Options {
+        warning_handler: Some(usvg::WarningHandler::new(move |msg| {
+            warnings2.lock().unwrap().push(msg.to_string());
+        })),
+        .. usvg::Options::default()
+    };
+
+    usvg::Tree::from_str(svg, &opt).unwrap();
+    assert_eq!(*warnings.lock().unwrap(), vec!["'rect' is not a valid filter primitive. Skipped."]);
+
+    // The handler is used only during the processing.
+    usvg::emit_warning(format_args!("test"));
+    assert_eq!(warnings.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn metadata_round_trip() {
+    let metadata = "<metadata xmlns:dc='http://purl.org/dc/elements/1.1/'>
+        <dc:creator>Jane &amp; John</dc:creator><!-- comment -->
+    </metadata>";
+
+    let svg = format!("<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>{}\
+                       <rect id='rect1' width='10' height='10'/></svg>", metadata);
+
+    let opt = usvg::Options::default();
+    let tree = usvg::Tree::from_str(&svg, &opt).unwrap();
+    assert_eq!(tree.svg_node().metadata.as_deref(), Some(metadata));
+
+    let out = tree.to_string(usvg::XmlOptions::default());
+    assert!(out.contains("    <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n        \
+                          <dc:creator>Jane &amp; John</dc:creator>\n    </metadata>\n"));
+
+    // The exported metadata must be preserved as is.
+    let tree2 = usvg::Tree::from_str(&out, &opt).unwrap();
+    assert_eq!(tree2.to_string(usvg::XmlOptions::default()), out);
+
+    // A subtree keeps the metadata too.
+    let sub_tree = usvg::NodeExt::tree(&tree.node_by_id("rect1").unwrap());
+    assert_eq!(sub_tree.svg_node().metadata, tree.svg_node().metadata);
+}
+
+#[test]
+fn per_axis_dpi() {
+    use usvg::FuzzyEq;
+
+    let svg = "
+    <svg width='2in' height='2in' xmlns='http://www.w3.org/2000/svg'>
+        <rect x='0.5in' y='0.5in' width='1in' height='1in'/>
+    </svg>
+    ";
+
+    let opt = usvg::Options {
+        dpi_x: Some(100.0),
+        dpi_y: Some(150.5),
+        .. usvg::Options::default()
+    };
+
+    let tree = usvg::Tree::from_str(svg, &opt).unwrap();
+    assert!(tree.svg_node().size.fuzzy_eq(&usvg::Size::new(200.0, 301.0).unwrap()));
+
+    let node = tree.root().descendants().find(|n| matches!(*n.borrow(), usvg::NodeKind::Path(_)))
+        .unwrap();
+    let bbox = match *node.borrow() {
+        usvg::NodeKind::Path(ref path) => path.data.bbox().unwrap(),
+        _ => unreachable!(),
+    };
+
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(50.0, 75.25, 100.0, 150.5).unwrap()));
+}
+
+#[test]
+fn element_at() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <clipPath id='clip1'>
+            <rect x='100' y='0' width='100' height='100'/>
+        </clipPath>
+        <rect id='frame' x='10' y='10' width='180' height='180'
+              fill='none' stroke='black' stroke-width='10'/>
+        <path id='path1' fill-rule='evenodd' transform='translate(20 20)'
+              d='M 0 0 H 160 V 160 H 0 Z M 40 40 H 120 V 120 H 40 Z'/>
+        <circle id='circle1' cx='150' cy='50' r='40' clip-path='url(#clip1)'/>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    assert_eq!(tree.element_at(30.0, 30.0).as_deref(), Some("path1"));
+    // A hole in an even-odd path.
+    assert_eq!(tree.element_at(100.0, 100.0), None);
+    // A stroke.
+    assert_eq!(tree.element_at(7.0, 100.0).as_deref(), Some("frame"));
+    assert_eq!(tree.element_at(2.0, 100.0), None);
+    // The topmost element.
+    assert_eq!(tree.element_at(150.0, 50.0).as_deref(), Some("circle1"));
+    // A clipped out part of the circle.
+    assert_eq!(tree.element_at(150.0, 105.0).as_deref(), Some("path1"));
+}
+
+#[test]
+fn element_to_path() {
+    use usvg::FuzzyEq;
+
+    let opt = usvg::Options::default();
+
+    let path = usvg::element_to_path(
+        "<rect x='10' y='20' width='50%' height='30' fill='green' stroke='black'
+               stroke-width='2' transform='translate(5 5)'/>",
+        &opt,
+    ).unwrap();
+
+    let bbox = path.data.bbox().unwrap();
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 20.0, 50.0, 30.0).unwrap()));
+    assert_eq!(path.transform, usvg::Transform::new_translate(5.0, 5.0));
+    assert!(matches!(path.fill.as_ref().unwrap().paint, usvg::Paint::Color(c) if c == usvg::Color::new(0, 128, 0)));
+    assert!(path.stroke.unwrap().width.value().fuzzy_eq(&2.0));
+
+    // Not a shape.
+    assert!(usvg::element_to_path("<g/>", &opt).is_none());
+    // An invalid geometry.
+    assert!(usvg::element_to_path("<circle r='0'/>", &opt).is_none());
+}
+
+#[test]
+fn root_size_units() {
+    use usvg::FuzzyEq;
+
+    let size = |width: &str, dpi: f64| {
+        let svg = format!(
+            "<svg width='{}' height='10' xmlns='http://www.w3.org/2000/svg'/>", width
+        );
+
+        let opt = usvg::Options { dpi, .. usvg::Options::default() };
+        let tree = usvg::Tree::from_str(&svg, &opt).unwrap();
+        let size = tree.svg_node().size;
+        size.width()
+    };
+
+    // Unitless and `px` values are always user units.
+    assert!(size("100", 96.0).fuzzy_eq(&100.0));
+    assert!(size("100px", 96.0).fuzzy_eq(&100.0));
+    assert!(size("100", 72.0).fuzzy_eq(&100.0));
+    assert!(size("100px", 72.0).fuzzy_eq(&100.0));
+    // Physical units depend on DPI.
+    assert!(size("75pt", 96.0).fuzzy_eq(&100.0));
+    assert!(size("75pt", 72.0).fuzzy_eq(&75.0));
+    assert!(size("6pc", 96.0).fuzzy_eq(&96.0));
+}
+
+#[test]
+fn bbox_with_stroke() {
+    use usvg::{FuzzyEq, NodeExt};
+
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <g transform='scale(2)'>
+            <path id='path1' d='M 10 10 L 20 10' stroke='black' stroke-width='4'/>
+            <path id='path2' d='M 10 30 L 20 30' stroke='black' stroke-width='4'
+                  vector-effect='non-scaling-stroke'/>
+        </g>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let bbox = |id| tree.node_by_id(id).unwrap().calculate_bbox().unwrap();
+
+    // A regular stroke is scaled along with the path.
+    assert!(bbox("path1").fuzzy_eq(&usvg::Rect::new(16.0, 16.0, 28.0, 8.0).unwrap()));
+    // A non-scaling stroke is in the root coordinates.
+    assert!(bbox("path2").fuzzy_eq(&usvg::Rect::new(18.0, 58.0, 24.0, 4.0).unwrap()));
+}
+
+#[test]
+fn keep_image_data() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'
+         xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <image width='100' height='100' xlink:href='not-existing.png'/>
+        <image width='100' height='100' xlink:href='not-existing.gif'/>
+    </svg>
+    ";
+
+    let images = |opt: &usvg::Options| {
+        let tree = usvg::Tree::from_str(svg, opt).unwrap();
+        let list: Vec<_> = tree.root().descendants().filter_map(|node| {
+            match *node.borrow() {
+                usvg::NodeKind::Image(ref img) => Some((img.data.clone(), img.format)),
+                _ => None,
+            }
+        }).collect();
+        list
+    };
+
+    assert!(images(&usvg::Options::default()).is_empty());
+
+    let opt = usvg::Options { keep_image_data: true, .. usvg::Options::default() };
+    let list = images(&opt);
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0].1, usvg::ImageFormat::PNG);
+    match list[0].0 {
+        usvg::ImageData::Path(ref path) => assert_eq!(path.to_str(), Some("not-existing.png")),
+        usvg::ImageData::Raw(_) => panic!("expected a path"),
+    }
+
+    // The path is relative to the SVG file.
+    let opt = usvg::Options {
+        path: Some("images/test.svg".into()),
+        keep_image_data: true,
+        .. usvg::Options::default()
+    };
+    match images(&opt)[0].0 {
+        usvg::ImageData::Path(ref path) => {
+            assert_eq!(path, std::path::Path::new("images/not-existing.png"));
+        }
+        usvg::ImageData::Raw(_) => panic!("expected a path"),
+    }
+}
+
+#[test]
+fn map_colors() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <linearGradient id='lg1'>
+            <stop offset='0' stop-color='white'/>
+            <stop offset='1' stop-color='#102030'/>
+        </linearGradient>
+        <rect width='100' height='100' fill='url(#lg1)' stroke='black'/>
+        <rect x='100' width='100' height='100' fill='url(#lg1)' stroke='#00ff00'/>
+        <filter id='filter1'>
+            <feFlood flood-color='black'/>
+            <feDiffuseLighting lighting-color='#102030'>
+                <feDistantLight/>
+            </feDiffuseLighting>
+        </filter>
+        <g filter='url(#filter1)'>
+            <rect width='100' height='100' stroke='black'/>
+        </g>
+    </svg>
+    ";
+
+    let mut tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    tree.map_colors(|c| usvg::Color::new(255 - c.red, 255 - c.green, 255 - c.blue));
+
+    let mut strokes = Vec::new();
+    let mut stops = Vec::new();
+    let mut filter_colors = Vec::new();
+    for node in tree.root().descendants() {
+        match *node.borrow() {
+            usvg::NodeKind::Path(ref path) => {
+                match path.stroke.as_ref().map(|s| &s.paint) {
+                    Some(usvg::Paint::Color(c)) => strokes.push(*c),
+                    _ => panic!("expected a color"),
+                }
+            }
+            usvg::NodeKind::LinearGradient(ref lg) => {
+                stops.extend(lg.base.stops.iter().map(|s| s.color));
+            }
+            usvg::NodeKind::Filter(ref filter) => {
+                for primitive in &filter.children {
+                    match primitive.kind {
+                        usvg::FilterKind::FeFlood(ref fe) => filter_colors.push(fe.color),
+                        usvg::FilterKind::FeDiffuseLighting(ref fe) => {
+                            filter_colors.push(fe.lighting_color)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    assert_eq!(strokes, vec![
+        usvg::Color::white(), usvg::Color::new(255, 0, 255), usvg::Color::white(),
+    ]);
+    // A shared gradient is inverted only once.
+    assert_eq!(stops, vec![usvg::Color::black(), usvg::Color::new(0xef, 0xdf, 0xcf)]);
+    assert_eq!(filter_colors, vec![usvg::Color::white(), usvg::Color::new(0xef, 0xdf, 0xcf)]);
+}
+
+#[test]
+fn from_reader_with_gzip() {
+    use std::io::Write;
+
+    let svg = "<svg xmlns='http://www.w3.org/2000/svg' width='200' height='200'>
+        <rect width='100' height='100' fill='green'/>
+    </svg>";
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(svg.as_bytes()).unwrap();
+    let svgz = encoder.finish().unwrap();
+
+    let opt = usvg::Options::default();
+    let plain = usvg::Tree::from_reader(svg.as_bytes(), &opt).unwrap();
+    let compressed = usvg::Tree::from_reader(svgz.as_slice(), &opt).unwrap();
+
+    let xml_opt = usvg::XmlOptions::default();
+    assert_eq!(MStr(&compressed.to_string(xml_opt)), MStr(&plain.to_string(xml_opt)));
+}
+
+fn rotated_text(rotate: &str) -> String {
+    let svg = format!("
+    <svg viewBox='0 0 200 100' xmlns='http://www.w3.org/2000/svg'>
+        <text x='10' y='50' font-family='DejaVu Sans' font-size='48' rotate='{}'>Text</text>
+    </svg>
+    ", rotate);
+
+    let tree = usvg::Tree::from_str(&svg, &test_options()).unwrap();
+    tree.to_string(usvg::XmlOptions::default())
+}
+
+#[test]
+fn text_single_rotate_value() {
+    // A single value is applied to all glyphs and not just to the first one.
+    assert_eq!(MStr(&rotated_text("15")), MStr(&rotated_text("15 15 15 15")));
+    assert_ne!(MStr(&rotated_text("15")), MStr(&rotated_text("15 0 0 0")));
+}
+
+fn text_path_data(text: &str) -> Vec<String> {
+    let svg = format!("
+    <svg viewBox='0 0 200 100' xmlns='http://www.w3.org/2000/svg'>
+        <g font-family='DejaVu Sans' font-size='48' text-anchor='middle'>{}</g>
+    </svg>
+    ", text);
+
+    let tree = usvg::Tree::from_str(&svg, &test_options()).unwrap();
+    let mut list = Vec::new();
+    for node in tree.root().descendants() {
+        if let usvg::NodeKind::Path(ref path) = *node.borrow() {
+            list.extend(path.data.iter().map(|seg| format!("{:?}", seg)));
+        }
+    }
+
+    list
+}
+
+#[test]
+fn text_x_list() {
+    // Each listed `x` starts a new text chunk, which is anchored separately.
+    let list = text_path_data("<text x='10 60 110' y='50'>ABC</text>");
+    let separate = text_path_data("\
+        <text x='10' y='50'>A</text>\
+        <text x='60' y='50'>B</text>\
+        <text x='110' y='50'>C</text>");
+    assert!(!list.is_empty());
+    assert_eq!(list, separate);
+}
+
+#[test]
+fn write_to() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='50' height='50' fill='green'/>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let mut data = Vec::new();
+    tree.write_to(&mut data, usvg::XmlOptions::default()).unwrap();
+    assert_eq!(MStr(std::str::from_utf8(&data).unwrap()),
+               MStr(&tree.to_string(usvg::XmlOptions::default())));
+}
+
+#[test]
+fn write_to_with_image() {
+    let image = base64::encode(&vec![0u8; 100_000]);
+    let svg = format!("
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'
+         xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <image width='50' height='50' xlink:href='data:image/png;base64,{}'/>
+        <rect width='50' height='50' fill='green'/>
+    </svg>
+    ", image);
+
+    let tree = usvg::Tree::from_str(&svg, &usvg::Options::default()).unwrap();
+    for indent in &[usvg::XmlIndent::None, usvg::XmlIndent::Spaces(2), usvg::XmlIndent::Tabs] {
+        let opt = usvg::XmlOptions {
+            indent: *indent,
+            attributes_indent: *indent,
+            use_single_quote: true,
+        };
+
+        let mut data = Vec::new();
+        tree.write_to(&mut data, opt).unwrap();
+        let text = std::str::from_utf8(&data).unwrap();
+        assert!(text.contains(&format!("'data:image/png;base64, {}'", image)));
+        assert_eq!(MStr(text), MStr(&tree.to_string(opt)));
+    }
+
+    // A write error must be returned as is.
+    struct LimitedWriter(usize);
+    impl std::io::Write for LimitedWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if buf.len() > self.0 {
+                return Err(std::io::Error::other("limit"));
+            }
+
+            self.0 -= buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let err = tree.write_to(LimitedWriter(1000), usvg::XmlOptions::default()).unwrap_err();
+    assert_eq!(err.to_string(), "limit");
+}
+
+#[test]
+fn default_font_family() {
+    let render = |attrs: &str| {
+        let svg = format!("
+        <svg viewBox='0 0 200 100' xmlns='http://www.w3.org/2000/svg'>
+            <text x='10' y='50' font-size='48' {}>Text</text>
+        </svg>
+        ", attrs);
+
+        let opt = usvg::Options {
+            font_family: "DejaVu Sans".to_string(),
+            ..test_options()
+        };
+        let tree = usvg::Tree::from_str(&svg, &opt).unwrap();
+        tree.to_string(usvg::XmlOptions::default())
+    };
+
+    let explicit = render("font-family='DejaVu Sans'");
+    assert!(explicit.contains("<path"));
+    // Used when `font-family` is not set.
+    assert_eq!(MStr(&render("")), MStr(&explicit));
+    // And when none of the listed families are available.
+    assert_eq!(MStr(&render("font-family='Unknown Font, Another Unknown Font'")), MStr(&explicit));
+}
+
+#[test]
+fn foreign_object_placeholder() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg' fill='green'>
+        <foreignObject x='20' y='30' width='100' height='50' stroke='red'>
+            <div xmlns='http://www.w3.org/1999/xhtml'>Text</div>
+        </foreignObject>
+    </svg>
+    ";
+
+    let convert = |placeholder: bool| {
+        let opt = usvg::Options {
+            foreign_object_placeholder: placeholder,
+            ..usvg::Options::default()
+        };
+        let tree = usvg::Tree::from_str(svg, &opt).unwrap();
+        tree.root().descendants()
+            .filter(|n| matches!(*n.borrow(), usvg::NodeKind::Path(_)))
+            .collect::<Vec<_>>()
+    };
+
+    // Skipped silently by default.
+    assert!(convert(false).is_empty());
+
+    let nodes = convert(true);
+    assert_eq!(nodes.len(), 1);
+    if let usvg::NodeKind::Path(ref path) = *nodes[0].borrow() {
+        assert_eq!(format!("{:?}", path.data.bbox()),
+                   format!("{:?}", usvg::Rect::new(20.0, 30.0, 100.0, 50.0)));
+        match path.fill.as_ref().map(|f| &f.paint) {
+            Some(usvg::Paint::Color(c)) => assert_eq!(*c, usvg::Color::new(211, 211, 211)),
+            _ => panic!("a color fill is expected"),
+        }
+        assert!(path.stroke.is_none());
+    } else {
+        panic!("a path is expected");
+    };
+}
+
+#[test]
+fn text_nested_tspan_dx() {
+    // Relative offsets are accumulated by the running text position.
+    let nested = text_path_data("\
+        <text x='20' y='50' text-anchor='start'>\
+            A<tspan dx='10'>B<tspan dx='15'>C<tspan dx='20'>D</tspan></tspan></tspan>\
+        </text>");
+    let flat = text_path_data("<text x='20' y='50' dx='0 10 15 20' text-anchor='start'>ABCD</text>");
+    let plain = text_path_data("<text x='20' y='50' text-anchor='start'>ABCD</text>");
+    assert!(!nested.is_empty());
+    assert_eq!(nested, flat);
+    assert_ne!(nested, plain);
+}
+
+#[test]
+fn css_transform() {
+    let convert = |attrs: &str| {
+        let svg = format!("
+        <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+            <rect x='50' y='50' width='50' height='50' {}/>
+        </svg>
+        ", attrs);
+
+        let tree = usvg::Tree::from_str(&svg, &usvg::Options::default()).unwrap();
+        tree.to_string(usvg::XmlOptions::default())
+    };
+
+    let rotated = convert("transform='rotate(45)'");
+    assert!(rotated.contains("transform="));
+    assert_eq!(MStr(&convert("style='transform:rotate(45deg)'")), MStr(&rotated));
+    assert_eq!(MStr(&convert("style='transform:rotate(0.125turn)'")), MStr(&rotated));
+    // CSS takes precedence over the attribute.
+    assert_eq!(MStr(&convert("transform='scale(2)' style='transform:rotate(45deg)'")),
+               MStr(&rotated));
+
+    assert_eq!(MStr(&convert("style='transform: translateX(10px) translateY(20px) scale(2, 3)'")),
+               MStr(&convert("transform='translate(10 20) scale(2 3)'")));
+}
+
+#[test]
+fn node_by_id() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <linearGradient id='lg1'>
+            <stop offset='0' stop-color='green'/>
+            <stop offset='1' stop-color='blue'/>
+        </linearGradient>
+        <g id='g1' opacity='0.5'>
+            <rect id='rect1' width='50' height='50' fill='url(#lg1)'/>
+        </g>
+    </svg>
+    ";
+
+    let opt = usvg::Options { keep_named_groups: true, ..usvg::Options::default() };
+    let mut tree = usvg::Tree::from_str(svg, &opt).unwrap();
+
+    let kind = |id: &str| tree.node_by_id(id).map(|n| match *n.borrow() {
+        usvg::NodeKind::Group(_) => "group",
+        usvg::NodeKind::Path(_) => "path",
+        usvg::NodeKind::LinearGradient(_) => "linear gradient",
+        _ => "other",
+    });
+
+    assert_eq!(kind("g1"), Some("group"));
+    assert_eq!(kind("rect1"), Some("path"));
+    assert_eq!(kind("lg1"), Some("linear gradient"));
+    assert_eq!(kind("missing"), None);
+    assert_eq!(kind(""), None);
+    assert!(tree.is_in_defs(&tree.node_by_id("lg1").unwrap()));
+
+    // Nodes added after the conversion are found too.
+    tree.append_to_defs(usvg::NodeKind::ClipPath(usvg::ClipPath {
+        id: "clip1".to_string(),
+        units: usvg::Units::UserSpaceOnUse,
+        transform: usvg::Transform::default(),
+        clip_path: None,
+    }));
+    assert!(tree.node_by_id("clip1").is_some());
+
+    // A renderable node is preferred over a `Defs` one with the same ID.
+    let mut g1 = tree.node_by_id("g1").unwrap();
+    let mut lg1 = g1.first_child().unwrap().make_deep_copy();
+    if let usvg::NodeKind::Path(ref mut path) = *lg1.borrow_mut() {
+        path.id = "lg1".to_string();
+    }
+    g1.append(lg1);
+    assert!(!tree.is_in_defs(&tree.node_by_id("lg1").unwrap()));
+
+    // Removed nodes are not found.
+    tree.node_by_id("rect1").unwrap().detach();
+    assert!(tree.node_by_id("rect1").is_none());
+}
+
+#[test]
+fn defs_by_id_gradient_stops() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <linearGradient id='lg1'>
+            <stop offset='0.2' stop-color='green'/>
+            <stop offset='0.8' stop-color='blue' stop-opacity='0.5'/>
+        </linearGradient>
+        <rect id='rect1' width='100' height='100' fill='url(#lg1)'/>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let node = tree.defs_by_id("lg1").unwrap();
+    if let usvg::NodeKind::LinearGradient(ref lg) = *node.borrow() {
+        let stops: Vec<_> = lg.stops.iter()
+            .map(|s| (s.offset.value(), s.color, s.opacity.value()))
+            .collect();
+        assert_eq!(stops, vec![
+            (0.2, usvg::Color::new(0, 128, 0), 1.0),
+            (0.8, usvg::Color::new(0, 0, 255), 0.5),
+        ]);
+    } else {
+        panic!("a linear gradient is expected");
+    };
+
+    // Renderable nodes are not in `defs`.
+    assert!(tree.defs_by_id("rect1").is_none());
+    assert!(tree.defs_by_id("missing").is_none());
+}
+
+#[test]
+fn font_relative_units() {
+    let stroke_width = |root_attrs: &str, attrs: &str| {
+        let svg = format!("
+        <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg' {}>
+            <g font-size='10'>
+                <rect width='100' height='100' stroke='black' {}/>
+            </g>
+        </svg>
+        ", root_attrs, attrs);
+
+        let tree = usvg::Tree::from_str(&svg, &usvg::Options::default()).unwrap();
+        let node = tree.root().descendants()
+            .find(|n| matches!(*n.borrow(), usvg::NodeKind::Path(_)))
+            .unwrap();
+        let width = match *node.borrow() {
+            usvg::NodeKind::Path(ref path) => path.stroke.as_ref().unwrap().width.value(),
+            _ => unreachable!(),
+        };
+        width
+    };
+
+    // `em` and `ex` are relative to the element's font size.
+    assert_eq!(stroke_width("", "font-size='20' stroke-width='0.1em'"), 2.0);
+    assert_eq!(stroke_width("", "font-size='20' stroke-width='0.2ex'"), 2.0);
+    // `rem` is relative to the root element's font size.
+    assert_eq!(stroke_width("font-size='20'", "stroke-width='0.1rem'"), 2.0);
+    assert_eq!(stroke_width("font-size='20'", "style='stroke-width:0.1rem'"), 2.0);
+    // Or to the default one.
+    assert_eq!(stroke_width("", "stroke-width='0.5rem'"), 6.0);
+    // Absolute root font size units are using the DPI.
+    assert_eq!(stroke_width("font-size='1in'", "stroke-width='0.5rem'"), 48.0);
+}
+
+#[test]
+fn rem_units_in_strings() {
+    use usvg::NodeExt;
+
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <linearGradient id='lg-2rem'>
+            <stop offset='0' stop-color='white'/>
+            <stop offset='1' stop-color='black'/>
+        </linearGradient>
+        <rect id='g2rem' width='10rem' height='100' fill='url(#lg-2rem)'/>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    assert!(tree.defs_by_id("lg-2rem").is_some());
+
+    let node = tree.root().descendants().find(|n| &*n.id() == "g2rem").unwrap();
+    match *node.borrow() {
+        usvg::NodeKind::Path(ref path) => {
+            assert_eq!(path.data.bbox().unwrap().width(), 120.0);
+            match path.fill.as_ref().unwrap().paint {
+                usvg::Paint::Link(ref id) => assert_eq!(id, "lg-2rem"),
+                _ => unreachable!(),
+            }
+        }
+        _ => unreachable!(),
+    };
+}
+
+#[test]
+fn stroke_width_options() {
+    let stroke_width = |attrs: &str, opt: &usvg::Options| {
+        let svg = format!("
+        <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+            <rect width='100' height='100' stroke='black' {}/>
+        </svg>
+        ", attrs);
+
+        let tree = usvg::Tree::from_str(&svg, opt).unwrap();
+        let node = tree.root().descendants()
+            .find(|n| matches!(*n.borrow(), usvg::NodeKind::Path(_)))
+            .unwrap();
+        let width = match *node.borrow() {
+            usvg::NodeKind::Path(ref path) => path.stroke.as_ref().map(|s| s.width.value()),
+            _ => unreachable!(),
+        };
+        width
+    };
+
+    let opt = usvg::Options::default();
+    assert_eq!(stroke_width("stroke-width='2mm'", &opt), Some(2.0 * 96.0 / 25.4));
+    assert_eq!(stroke_width("", &opt), Some(1.0));
+
+    let opt = usvg::Options { dpi: 300.0, default_stroke_width: 3.0, ..usvg::Options::default() };
+    assert_eq!(stroke_width("stroke-width='2mm'", &opt), Some(2.0 * 300.0 / 25.4));
+    assert_eq!(stroke_width("", &opt), Some(3.0));
+    // An explicit width is not affected.
+    assert_eq!(stroke_width("stroke-width='1'", &opt), Some(1.0));
+}
+
+#[test]
+fn from_str_chunked() {
+    use usvg::NodeExt;
+
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <linearGradient id='lg1'>
+            <stop offset='0' stop-color='white'/>
+            <stop offset='1' stop-color='black'/>
+        </linearGradient>
+        <rect id='rect1' width='10' height='10' fill='url(#lg1)'/>
+        <g/>
+        <rect id='rect2' x='20' width='10' height='10'/>
+        <g id='g1'><rect id='rect3' x='40' width='10' height='10' fill='url(#lg1)'/></g>
+    </svg>
+    ";
+
+    let chunks = |svg: &str| {
+        let mut chunks = Vec::new();
+        usvg::Tree::from_str_chunked(svg, &usvg::Options::default(), |tree| {
+            assert!(tree.defs_by_id("lg1").is_some());
+            let ids: Vec<_> = tree.root().children()
+                .filter(|n| !matches!(*n.borrow(), usvg::NodeKind::Defs))
+                .map(|n| n.id().to_string())
+                .collect();
+            chunks.push(ids.join(" "));
+        }).unwrap();
+        chunks
+    };
+
+    // Empty elements are skipped and groups are ungrouped just like in `from_str`.
+    assert_eq!(chunks(svg), vec!["rect1", "rect2", "rect3"]);
+
+    // Compressed data is supported too.
+    let mut data = Vec::new();
+    {
+        use std::io::Write;
+        let mut enc = flate2::write::GzEncoder::new(&mut data, flate2::Compression::default());
+        enc.write_all(svg.as_bytes()).unwrap();
+        enc.finish().unwrap();
+    }
+    let mut count = 0;
+    usvg::Tree::from_data_chunked(&data, &usvg::Options::default(), |_| count += 1).unwrap();
+    assert_eq!(count, 3);
+
+    // A root element that requires a group cannot be split.
+    let svg2 = svg.replacen("<svg ", "<svg opacity='0.5' ", 1);
+    assert_eq!(chunks(&svg2).len(), 1);
+
+    // `none` links do not require a group.
+    let svg2 = svg.replacen("<svg ", "<svg clip-path='none' mask='none' filter='none' ", 1);
+    assert_eq!(chunks(&svg2).len(), 3);
+
+    // The root element is converted only once, so each warning is emitted only once.
+    let warnings = std::sync::Arc::new(std::sync::Mutex::new(0));
+    let counter = warnings.clone();
+    let opt = usvg::Options {
+        warning_handler: Some(usvg::WarningHandler::new(move |_| *counter.lock().unwrap() += 1)),
+        .. usvg::Options::default()
+    };
+    let svg2 = svg
+        .replacen("<svg ", "<svg filter='url(#filter1)' ", 1)
+        .replacen("<g/>", "<filter id='filter1'><feDropShadow/></filter>", 1);
+    usvg::Tree::from_str_chunked(&svg2, &opt, |_| {}).unwrap();
+    assert_eq!(*warnings.lock().unwrap(), 1);
+}
+
+#[test]
+fn stop_style() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <linearGradient id='lg1'>
+            <stop offset='0' style='stop-color:#f00;stop-opacity:.5'/>
+            <stop offset='1' stop-color='green' stop-opacity='0.2'
+                  style='stop-color:blue; stop-opacity:0.7'/>
+        </linearGradient>
+        <rect width='100' height='100' fill='url(#lg1)'/>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let node = tree.defs_by_id("lg1").unwrap();
+    if let usvg::NodeKind::LinearGradient(ref lg) = *node.borrow() {
+        let stops: Vec<_> = lg.stops.iter()
+            .map(|s| (s.color, s.opacity.value()))
+            .collect();
+        // `style` has a higher priority than presentation attributes.
+        assert_eq!(stops, vec![
+            (usvg::Color::new(255, 0, 0), 0.5),
+            (usvg::Color::new(0, 0, 255), 0.7),
+        ]);
+    } else {
+        panic!("a linear gradient is expected");
+    };
+}
+
+#[test]
+fn validate() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'
+         xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <title>Title</title>
+        <clipPath id='clip1'>
+            <rect width='10' height='10' clip-path='url(#clip1)'/>
+        </clipPath>
+        <rect id='rect1' width='100' height='100' clip-path='url(#missing)'/>
+        <rect id='rect2' width='-100' height='100' fill='url(#missing) red'/>
+        <rect id='rect3' width='100' height='100' filter='url(#missing)'/>
+        <use id='use1' xlink:href='#missing'/>
+        <hatch id='hatch1'/>
+    </svg>
+    ";
+
+    let issues = usvg::validate(svg, &usvg::Options::default()).unwrap();
+    let issues: Vec<_> = issues.iter().map(|i| i.to_string()).collect();
+    assert_eq!(issues, vec![
+        "error: 'use1': 'use' references a non-existing element",
+        "warning: 'hatch1': 'hatch' is not supported and will be skipped",
+        "warning: 'rect1': 'clip-path' references a non-existing element '#missing'",
+        "warning: 'rect2': 'fill' references a non-existing element '#missing', the fallback will be used",
+        "error: 'rect3': 'filter' references a non-existing element '#missing'",
+        "error: 'rect2': 'width' cannot be negative",
+        "error: recursive 'clip-path' link",
+    ]);
+
+    let svg = "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'/>";
+    assert!(usvg::validate(svg, &usvg::Options::default()).unwrap().is_empty());
+
+    assert!(usvg::validate("<svg", &usvg::Options::default()).is_err());
+}
+
+#[test]
+fn text_length() {
+    let bbox = |attrs: &str| {
+        let svg = format!("
+        <svg viewBox='0 0 200 100' xmlns='http://www.w3.org/2000/svg'>
+            <text x='180' y='50' font-family='DejaVu Sans' font-size='32'
+                  text-anchor='end' {}>Squeeze</text>
+        </svg>
+        ", attrs);
+
+        let tree = usvg::Tree::from_str(&svg, &test_options()).unwrap();
+        let node = tree.root().descendants()
+            .find(|n| matches!(*n.borrow(), usvg::NodeKind::Path(_)))
+            .unwrap();
+        let bbox = match *node.borrow() {
+            usvg::NodeKind::Path(ref path) => path.data.bbox().unwrap(),
+            _ => unreachable!(),
+        };
+        bbox
+    };
+
+    // The chunk should end at `x` and start at `x - textLength`.
+    let plain = bbox("");
+    for adjust in &["spacing", "spacingAndGlyphs"] {
+        let r = bbox(&format!("textLength='150' lengthAdjust='{}'", adjust));
+        assert!((r.x() - 30.0).abs() < 5.0, "{}: {:?}", adjust, r);
+        assert!(r.right() <= 180.0);
+        assert!(r.width() > plain.width());
+    }
+
+    // Only spacing is changed, so glyphs keep their height.
+    let spacing = bbox("textLength='150'");
+    let glyphs = bbox("textLength='150' lengthAdjust='spacingAndGlyphs'");
+    assert!((spacing.height() - glyphs.height()).abs() < 0.001);
+    assert!((spacing.height() - plain.height()).abs() < 0.001);
+
+    // Invalid values are ignored.
+    assert_eq!(format!("{:?}", bbox("textLength='-10'")), format!("{:?}", plain));
+}
+
+#[test]
+fn optimize() {
+    use usvg::{FuzzyEq, NodeExt};
+
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <linearGradient id='lg1'>
+            <stop offset='0' stop-color='white'/>
+            <stop offset='1' stop-color='black'/>
+        </linearGradient>
+        <linearGradient id='lg2'>
+            <stop offset='0' stop-color='white'/>
+            <stop offset='1' stop-color='black'/>
+        </linearGradient>
+        <g>
+            <rect x='10.123456' width='50' height='50' fill='url(#lg1)'/>
+            <rect x='80' width='50' height='50' fill='url(#lg2)'/>
+            <rect x='80' width='50' height='50' fill='green' visibility='hidden'/>
+        </g>
+        <g>
+            <rect x='80' width='50' height='50' fill='none'/>
+        </g>
+        <g transform='translate(10)'>
+            <rect x='80' y='100' width='50' height='50' fill='green'/>
+        </g>
+        <g opacity='0.5'/>
+    </svg>
+    ";
+
+    let optimize = |optimize_opt: &usvg::OptimizeOptions| {
+        let text = usvg::optimize(svg, &usvg::Options::default(), optimize_opt).unwrap();
+        assert!(!text.contains('\n'));
+        text
+    };
+
+    let none = usvg::OptimizeOptions {
+        remove_empty_groups: false,
+        ungroup_groups: false,
+        deduplicate_gradients: false,
+        remove_invisible_paths: false,
+        precision: None,
+    };
+
+    let text = optimize(&none);
+    assert!(text.contains("lg2"));
+    assert_eq!(text.matches("<path").count(), 5);
+    assert_eq!(text.matches("<g").count(), 2);
+    assert!(text.contains("10.123456"));
+
+    let text = optimize(&usvg::OptimizeOptions { remove_empty_groups: true, ..none });
+    assert_eq!(text.matches("<g").count(), 1);
+    assert!(text.contains("<g transform="));
+
+    let text = optimize(&usvg::OptimizeOptions { ungroup_groups: true, ..none });
+    assert_eq!(text.matches("<g").count(), 1);
+    assert!(text.contains("opacity=\"0.5\""));
+
+    let text = optimize(&usvg::OptimizeOptions { deduplicate_gradients: true, ..none });
+    assert!(!text.contains("lg2"));
+    assert_eq!(text.matches("url(#lg1)").count(), 2);
+
+    let text = optimize(&usvg::OptimizeOptions { remove_invisible_paths: true, ..none });
+    assert_eq!(text.matches("<path").count(), 3);
+
+    let text = optimize(&usvg::OptimizeOptions { precision: Some(2), ..none });
+    assert!(text.contains("10.12 "));
+    assert!(!text.contains("10.123"));
+
+    // The optimized document has the same content.
+    let text = optimize(&usvg::OptimizeOptions { precision: None, ..usvg::OptimizeOptions::default() });
+    let tree1 = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let tree2 = usvg::Tree::from_str(&text, &usvg::Options::default()).unwrap();
+    let bbox1 = tree1.root().calculate_bbox().unwrap();
+    let bbox2 = tree2.root().calculate_bbox().unwrap();
+    assert!(bbox1.fuzzy_eq(&bbox2));
+}
+
+#[test]
+fn container_size() {
+    let svg = "
+    <svg width='100%' height='50%' viewBox='0 0 100 50' xmlns='http://www.w3.org/2000/svg'/>
+    ";
+
+    // Percentages are relative to the viewBox by default.
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    assert_eq!(tree.svg_node().size.width(), 100.0);
+    assert_eq!(tree.svg_node().size.height(), 25.0);
+
+    let opt = usvg::Options {
+        container_size: usvg::Size::new(800.0, 600.0),
+        ..usvg::Options::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &opt).unwrap();
+    assert_eq!(tree.svg_node().size.width(), 800.0);
+    assert_eq!(tree.svg_node().size.height(), 300.0);
+    assert_eq!(tree.svg_node().view_box.rect.size().width(), 100.0);
+
+    // A viewBox is not required when a container is set.
+    let svg = "<svg width='100%' height='100%' xmlns='http://www.w3.org/2000/svg'/>";
+    assert!(usvg::Tree::from_str(svg, &usvg::Options::default()).is_err());
+    let tree = usvg::Tree::from_str(svg, &opt).unwrap();
+    assert_eq!(tree.svg_node().size.width(), 800.0);
+    assert_eq!(tree.svg_node().size.height(), 600.0);
+}
+
+#[test]
+fn stats() {
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'
+         xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <linearGradient id='lg1'>
+            <stop offset='0' stop-color='white'/>
+            <stop offset='1' stop-color='black'/>
+        </linearGradient>
+        <pattern id='patt1' width='20' height='20' patternUnits='userSpaceOnUse'>
+            <rect width='10' height='10' fill='green'/>
+        </pattern>
+        <clipPath id='clip1'>
+            <rect width='100' height='100'/>
+        </clipPath>
+        <rect width='200' height='200' fill='url(#lg1)'/>
+        <g clip-path='url(#clip1)'>
+            <g opacity='0.5'>
+                <rect width='200' height='200' fill='url(#patt1)'/>
+            </g>
+        </g>
+        <image width='20' height='20' xlink:href='data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAQAAAAECAIAAAAmkwkpAAAAD0lEQVR4nGNgaGBAIOI4ANA0CAF5jtVRAAAAAElFTkSuQmCC'/>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &test_options()).unwrap();
+    assert_eq!(tree.stats(), usvg::Stats {
+        paths: 4,
+        path_segments: 20,
+        images: 1,
+        images_decoded_size: 64,
+        gradients: 1,
+        patterns: 1,
+        clip_paths: 1,
+        max_depth: 3,
+    });
+
+    let svg = "
+    <svg viewBox='0 0 200 100' xmlns='http://www.w3.org/2000/svg'>
+        <text x='10' y='50' font-family='DejaVu Sans'>Text <tspan fill='green'>span</tspan></text>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &test_options()).unwrap();
+    let stats = tree.stats();
+    assert_eq!(stats.paths, 2);
+    assert_eq!(stats.max_depth, 1);
+
+    // A JPEG header with a 5x3 image.
+    let jpeg = [
+        0xFF, 0xD8,
+        0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F', 0, 1, 1, 0, 0, 1, 0, 1, 0, 0,
+        0xFF, 0xC0, 0x00, 0x11, 0x08, 0x00, 0x03, 0x00, 0x05, 0x03,
+    ];
+    let svg = format!("
+    <svg viewBox='0 0 200 100' xmlns='http://www.w3.org/2000/svg'
+         xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <image width='20' height='20' xlink:href='data:image/jpeg;base64,{}'/>
+    </svg>
+    ", base64::encode(&jpeg[..]));
+
+    let tree = usvg::Tree::from_str(&svg, &test_options()).unwrap();
+    assert_eq!(tree.stats().images_decoded_size, 5 * 3 * 4);
+}